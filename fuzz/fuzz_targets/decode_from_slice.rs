@@ -0,0 +1,26 @@
+#![no_main]
+use std::str::FromStr;
+
+use ethereum_abi::{Type, Value};
+use libfuzzer_sys::fuzz_target;
+
+// Fuzz input layout: a comma separated list of types, a NUL byte and the
+// ABI encoded data to decode, e.g. `string,uint256[]\0<data>`.
+fuzz_target!(|data: &[u8]| {
+    let mut parts = data.splitn(2, |b| *b == 0);
+
+    let (tys, input) = match (parts.next(), parts.next()) {
+        (Some(tys), Some(input)) => (tys, input),
+        _ => return,
+    };
+
+    let tys = match std::str::from_utf8(tys)
+        .ok()
+        .and_then(|tys| Type::from_str(&format!("({})", tys)).ok())
+    {
+        Some(Type::Tuple(tys)) => tys.into_iter().map(|(_, ty)| ty).collect::<Vec<_>>(),
+        _ => return,
+    };
+
+    let _ = Value::decode_from_slice(input, &tys);
+});
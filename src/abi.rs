@@ -1,8 +1,13 @@
-use anyhow::{anyhow, Error, Result};
 use ethereum_types::H256;
-use serde::{de::Visitor, Deserialize};
+use serde::{de::Visitor, Deserialize, Serialize};
+use std::collections::HashMap;
 
-use crate::{params::Param, DecodedParams, Event, Value};
+use crate::{
+    human_readable::{parse_fragment, Fragment},
+    keccak256,
+    params::Param,
+    DecodeOptions, DecodedParams, Error, Event, Result, Type, Value,
+};
 
 /// Contract ABI (Abstract Binary Interface).
 ///
@@ -20,7 +25,7 @@ use crate::{params::Param, DecodedParams, Event, Value};
 ///
 /// let abi = Abi::from_str(abi_json).unwrap();
 /// ```
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct Abi {
     /// Contract constructor definition (if it defines one).
     pub constructor: Option<Constructor>,
@@ -28,10 +33,12 @@ pub struct Abi {
     pub functions: Vec<Function>,
     /// Contract defined events.
     pub events: Vec<Event>,
-    /// Whether the contract has the receive method defined.
-    pub has_receive: bool,
-    /// Whether the contract has the fallback method defined.
-    pub has_fallback: bool,
+    /// Contract defined custom errors.
+    pub errors: Vec<AbiError>,
+    /// Contract receive function (if it defines one).
+    pub receive: Option<Receive>,
+    /// Contract fallback function (if it defines one).
+    pub fallback: Option<Fallback>,
 }
 
 impl Abi {
@@ -42,23 +49,185 @@ impl Abi {
     {
         Ok(serde_json::from_reader(rdr)?)
     }
+
+    /// Parses a human-readable ABI definition.
+    ///
+    /// ```
+    /// use ethereum_abi::Abi;
+    ///
+    /// let abi = Abi::from_human_readable(&[
+    ///     "function transfer(address to, uint256 amount) external returns (bool)",
+    ///     "event Transfer(address indexed from, address indexed to, uint256 value)",
+    /// ])
+    /// .unwrap();
+    /// ```
+    pub fn from_human_readable<S>(fragments: &[S]) -> Result<Abi>
+    where
+        S: AsRef<str>,
+    {
+        let mut abi = Abi::default();
+
+        for fragment in fragments {
+            match parse_fragment(fragment.as_ref())? {
+                Fragment::Constructor(constructor) => abi.constructor = Some(constructor),
+                Fragment::Function(f) => abi.functions.push(f),
+                Fragment::Event(e) => abi.events.push(e),
+                Fragment::Error(e) => abi.errors.push(e),
+                Fragment::Receive(receive) => abi.receive = Some(receive),
+                Fragment::Fallback(fallback) => abi.fallback = Some(fallback),
+            }
+        }
+
+        Ok(abi)
+    }
+
+    /// Returns the ABI definition in human-readable form, one fragment per entry.
+    pub fn to_human_readable(&self) -> Vec<String> {
+        let mut fragments = vec![];
+
+        if let Some(constructor) = &self.constructor {
+            fragments.push(constructor.to_string());
+        }
+
+        fragments.extend(self.functions.iter().map(ToString::to_string));
+        fragments.extend(self.events.iter().map(ToString::to_string));
+        fragments.extend(self.errors.iter().map(ToString::to_string));
+
+        fragments.extend(self.receive.iter().map(ToString::to_string));
+        fragments.extend(self.fallback.iter().map(ToString::to_string));
+
+        fragments
+    }
 }
 
 impl Abi {
-    // Decode function input from slice.
+    /// Builds a selector and topic index for constant-time lookups, see [`AbiIndex`].
+    pub fn index(&self) -> AbiIndex<'_> {
+        AbiIndex::new(self)
+    }
+
+    /// Returns the function with the given name.
+    ///
+    /// Fails if the function is overloaded, see [`Abi::functions_by_name`],
+    /// [`Abi::function_by_signature`] and [`Abi::function_for_values`].
+    pub fn function(&self, name: &str) -> Result<&Function> {
+        match self.functions_by_name(name)[..] {
+            [] => Err(Error::UnknownFunction(name.to_string())),
+            [f] => Ok(f),
+            _ => Err(Error::AmbiguousFunction(name.to_string())),
+        }
+    }
+
+    /// Returns all the functions (i.e. overloads) with the given name.
+    pub fn functions_by_name(&self, name: &str) -> Vec<&Function> {
+        self.functions.iter().filter(|f| f.name == name).collect()
+    }
+
+    /// Returns the function with the given signature, e.g. `transfer(address,uint256)`.
+    ///
+    /// Whitespace and type aliases are accepted, e.g. `f(uint, bool)` matches
+    /// `f(uint256,bool)`.
+    pub fn function_by_signature(&self, signature: &str) -> Result<&Function> {
+        let invalid = || Error::TypeParse(signature.to_string());
+
+        let compact: String = signature.split_whitespace().collect();
+        let (name, params) = compact.split_at(compact.find('(').ok_or_else(invalid)?);
+        // empty tuples are not valid types, but are valid param lists
+        let params = match params {
+            "()" => Type::Tuple(vec![]),
+            _ => params.parse().map_err(|_| invalid())?,
+        };
+
+        // tuples are displayed as canonical parenthesized type lists
+        let signature = format!("{}{}", name, params);
+
+        self.functions
+            .iter()
+            .find(|f| f.signature() == signature)
+            .ok_or(Error::UnknownFunction(signature))
+    }
+
+    /// Returns the function with the given selector (method id).
+    ///
+    /// This scans the functions and hashes each signature, use [`Abi::index`]
+    /// for repeated lookups.
+    pub fn function_by_selector(&self, selector: [u8; 4]) -> Result<&Function> {
+        self.functions
+            .iter()
+            .find(|f| f.method_id() == selector)
+            .ok_or(Error::UnknownSelector(selector))
+    }
+
+    /// Returns the event with the given topic.
+    ///
+    /// This scans the events and hashes each signature, use [`Abi::index`]
+    /// for repeated lookups.
+    pub fn event_by_topic(&self, topic: &H256) -> Result<&Event> {
+        self.events
+            .iter()
+            .find(|e| e.topic() == *topic)
+            .ok_or(Error::UnknownEventTopic(*topic))
+    }
+
+    /// Returns the custom error with the given selector.
+    ///
+    /// This scans the errors and hashes each signature, use [`Abi::index`]
+    /// for repeated lookups.
+    pub fn error_by_selector(&self, selector: [u8; 4]) -> Result<&AbiError> {
+        self.errors
+            .iter()
+            .find(|e| e.selector() == selector)
+            .ok_or(Error::UnknownSelector(selector))
+    }
+
+    /// Returns the function with the given name whose inputs match the given
+    /// values, resolving overloads.
+    pub fn function_for_values(&self, name: &str, values: &[Value]) -> Result<&Function> {
+        let overloads = self.functions_by_name(name);
+
+        let mut matching = overloads
+            .iter()
+            .filter(|f| check_input_values(&f.inputs, values).is_ok());
+
+        match (matching.next(), matching.next()) {
+            (Some(f), None) => Ok(f),
+            (Some(_), Some(_)) => Err(Error::AmbiguousFunction(name.to_string())),
+            // report why the values do not match the function
+            (None, _) if overloads.len() == 1 => {
+                check_input_values(&overloads[0].inputs, values)?;
+                Ok(overloads[0])
+            }
+            (None, _) if overloads.is_empty() => Err(Error::UnknownFunction(name.to_string())),
+            (None, _) => Err(Error::InvalidValue(format!(
+                "no overload of function {} matches the given values",
+                name
+            ))),
+        }
+    }
+
+    /// Decode function input from slice.
+    ///
+    /// The function lookup is linear in the number of functions and hashes
+    /// each signature. When decoding many inputs, build an index once with
+    /// [`Abi::index`] and use [`AbiIndex::decode_input_from_slice`].
     pub fn decode_input_from_slice<'a>(
         &'a self,
         input: &[u8],
     ) -> Result<(&'a Function, DecodedParams)> {
-        let f = self
-            .functions
-            .iter()
-            .find(|f| f.method_id() == input[0..4])
-            .ok_or_else(|| anyhow!("ABI function not found"))?;
+        self.decode_input_from_slice_with(input, &DecodeOptions::default())
+    }
 
-        let decoded_params = f.decode_input_from_slice(&input[4..])?;
+    /// Decode function input from slice using the given decode options.
+    ///
+    /// Looks the function up as in [`Abi::decode_input_from_slice`].
+    pub fn decode_input_from_slice_with<'a>(
+        &'a self,
+        input: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<(&'a Function, DecodedParams)> {
+        let f = self.function_by_selector(read_selector(input)?)?;
 
-        Ok((&f, decoded_params))
+        Ok((f, f.decode_input_from_slice_with(&input[4..], options)?))
     }
 
     // Decode function input from hex string.
@@ -71,26 +240,250 @@ impl Abi {
         self.decode_input_from_slice(&slice)
     }
 
+    /// Decode function output (return data) from slice.
+    ///
+    /// The function is looked up by name or, if no function has the given name,
+    /// by its hex encoded method id (e.g. `0xa9059cbb`). Overloaded functions
+    /// can only be looked up by method id.
+    pub fn decode_output<'a>(
+        &'a self,
+        name_or_selector: &str,
+        data: &[u8],
+    ) -> Result<(&'a Function, DecodedParams)> {
+        self.decode_output_with(name_or_selector, data, &DecodeOptions::default())
+    }
+
+    /// Decode function output (return data) from slice using the given decode
+    /// options. The function is looked up as in [`Abi::decode_output`].
+    pub fn decode_output_with<'a>(
+        &'a self,
+        name_or_selector: &str,
+        data: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<(&'a Function, DecodedParams)> {
+        let f = match self.function(name_or_selector) {
+            Err(Error::UnknownFunction(_)) => {
+                let mut selector = [0u8; 4];
+                hex::decode_to_slice(name_or_selector.trim_start_matches("0x"), &mut selector)
+                    .map_err(|_| Error::UnknownFunction(name_or_selector.to_string()))?;
+
+                self.function_by_selector(selector)?
+            }
+            res => res?,
+        };
+
+        let decoded_params = f.decode_output_from_slice_with(data, options)?;
+
+        Ok((f, decoded_params))
+    }
+
+    /// Encode function input for the function with the given name.
+    ///
+    /// Overloaded functions are resolved from the values' types, see
+    /// [`Abi::function_for_values`].
+    pub fn encode_input(&self, name: &str, values: &[Value]) -> Result<Vec<u8>> {
+        self.function_for_values(name, values)?.encode_input(values)
+    }
+
     /// Decode event data from slice.
+    ///
+    /// The event lookup is linear in the number of events and hashes each
+    /// signature. When decoding many logs, build an index once with
+    /// [`Abi::index`] and use [`AbiIndex::decode_log_from_slice`].
     pub fn decode_log_from_slice<'a>(
         &'a self,
         topics: &[H256],
         data: &[u8],
     ) -> Result<(&'a Event, DecodedParams)> {
-        if topics.is_empty() {
-            return Err(anyhow!("missing event topic id"));
+        self.decode_log_from_slice_with(topics, data, &DecodeOptions::default())
+    }
+
+    /// Decode event data from slice using the given decode options.
+    ///
+    /// Looks the event up as in [`Abi::decode_log_from_slice`].
+    pub fn decode_log_from_slice_with<'a>(
+        &'a self,
+        topics: &[H256],
+        data: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<(&'a Event, DecodedParams)> {
+        let e = self.event_by_topic(read_topic(topics)?)?;
+
+        Ok((e, e.decode_data_from_slice_with(topics, data, options)?))
+    }
+
+    /// Decode custom error from revert data.
+    ///
+    /// The error lookup is linear in the number of errors and hashes each
+    /// signature. When decoding many reverts, build an index once with
+    /// [`Abi::index`] and use [`AbiIndex::decode_error_from_slice`].
+    pub fn decode_error_from_slice<'a>(
+        &'a self,
+        revert_data: &[u8],
+    ) -> Result<(&'a AbiError, DecodedParams)> {
+        self.decode_error_from_slice_with(revert_data, &DecodeOptions::default())
+    }
+
+    /// Decode custom error from revert data using the given decode options.
+    ///
+    /// Looks the error up as in [`Abi::decode_error_from_slice`].
+    pub fn decode_error_from_slice_with<'a>(
+        &'a self,
+        revert_data: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<(&'a AbiError, DecodedParams)> {
+        let e = self.error_by_selector(read_selector(revert_data)?)?;
+
+        Ok((
+            e,
+            e.decode_input_from_slice_with(&revert_data[4..], options)?,
+        ))
+    }
+}
+
+/// Selector and topic index of an ABI's functions, events and custom errors.
+///
+/// Lookups are constant-time and compute no hashes, which pays off when
+/// decoding many inputs, logs or errors against the same ABI. The index
+/// borrows the ABI, so it can't outlive changes to it. The first definition
+/// wins when several share a selector or topic.
+///
+/// ```no_run
+/// use ethereum_abi::{Abi, Result};
+/// use ethereum_types::H256;
+///
+/// fn decode_logs(abi: &Abi, logs: &[(Vec<H256>, Vec<u8>)]) -> Result<()> {
+///     let index = abi.index();
+///
+///     for (topics, data) in logs {
+///         let (event, params) = index.decode_log_from_slice(topics, data)?;
+///         println!("{}: {:?}", event.name, params);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AbiIndex<'a> {
+    functions: HashMap<[u8; 4], &'a Function>,
+    events: HashMap<H256, &'a Event>,
+    errors: HashMap<[u8; 4], &'a AbiError>,
+}
+
+impl<'a> AbiIndex<'a> {
+    /// Indexes the given ABI.
+    pub fn new(abi: &'a Abi) -> Self {
+        let mut index = Self {
+            functions: HashMap::new(),
+            events: HashMap::new(),
+            errors: HashMap::new(),
+        };
+
+        for f in &abi.functions {
+            index.functions.entry(f.method_id()).or_insert(f);
         }
 
-        let e = self
-            .events
-            .iter()
-            .find(|e| e.topic() == topics[0])
-            .ok_or_else(|| anyhow!("ABI event not found"))?;
+        for e in &abi.events {
+            index.events.entry(e.topic()).or_insert(e);
+        }
+
+        for e in &abi.errors {
+            index.errors.entry(e.selector()).or_insert(e);
+        }
+
+        index
+    }
+
+    /// Returns the function with the given selector (method id).
+    pub fn function_by_selector(&self, selector: [u8; 4]) -> Result<&'a Function> {
+        self.functions
+            .get(&selector)
+            .copied()
+            .ok_or(Error::UnknownSelector(selector))
+    }
+
+    /// Returns the event with the given topic.
+    pub fn event_by_topic(&self, topic: &H256) -> Result<&'a Event> {
+        self.events
+            .get(topic)
+            .copied()
+            .ok_or(Error::UnknownEventTopic(*topic))
+    }
+
+    /// Returns the custom error with the given selector.
+    pub fn error_by_selector(&self, selector: [u8; 4]) -> Result<&'a AbiError> {
+        self.errors
+            .get(&selector)
+            .copied()
+            .ok_or(Error::UnknownSelector(selector))
+    }
+
+    /// Decode function input from slice, see [`Abi::decode_input_from_slice`].
+    pub fn decode_input_from_slice(&self, input: &[u8]) -> Result<(&'a Function, DecodedParams)> {
+        self.decode_input_from_slice_with(input, &DecodeOptions::default())
+    }
 
-        let decoded_params = e.decode_data_from_slice(topics, data)?;
+    /// Decode function input from slice using the given decode options.
+    pub fn decode_input_from_slice_with(
+        &self,
+        input: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<(&'a Function, DecodedParams)> {
+        let f = self.function_by_selector(read_selector(input)?)?;
+
+        Ok((f, f.decode_input_from_slice_with(&input[4..], options)?))
+    }
+
+    /// Decode event data from slice, see [`Abi::decode_log_from_slice`].
+    pub fn decode_log_from_slice(
+        &self,
+        topics: &[H256],
+        data: &[u8],
+    ) -> Result<(&'a Event, DecodedParams)> {
+        self.decode_log_from_slice_with(topics, data, &DecodeOptions::default())
+    }
+
+    /// Decode event data from slice using the given decode options.
+    pub fn decode_log_from_slice_with(
+        &self,
+        topics: &[H256],
+        data: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<(&'a Event, DecodedParams)> {
+        let e = self.event_by_topic(read_topic(topics)?)?;
+
+        Ok((e, e.decode_data_from_slice_with(topics, data, options)?))
+    }
 
-        Ok((&e, decoded_params))
+    /// Decode custom error from revert data, see [`Abi::decode_error_from_slice`].
+    pub fn decode_error_from_slice(
+        &self,
+        revert_data: &[u8],
+    ) -> Result<(&'a AbiError, DecodedParams)> {
+        self.decode_error_from_slice_with(revert_data, &DecodeOptions::default())
     }
+
+    /// Decode custom error from revert data using the given decode options.
+    pub fn decode_error_from_slice_with(
+        &self,
+        revert_data: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<(&'a AbiError, DecodedParams)> {
+        let e = self.error_by_selector(read_selector(revert_data)?)?;
+
+        Ok((
+            e,
+            e.decode_input_from_slice_with(&revert_data[4..], options)?,
+        ))
+    }
+}
+
+// Returns the event topic of a non-anonymous event log.
+fn read_topic(topics: &[H256]) -> Result<&H256> {
+    topics.first().ok_or(Error::TopicCountMismatch {
+        expected: 1,
+        got: 0,
+    })
 }
 
 impl std::str::FromStr for Abi {
@@ -111,6 +504,72 @@ impl<'de> Deserialize<'de> for Abi {
     }
 }
 
+impl Serialize for Abi {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut entries = vec![];
+
+        if let Some(constructor) = &self.constructor {
+            entries.push(AbiEntry {
+                inputs: Some(constructor.inputs.clone()),
+                state_mutability: Some(constructor.state_mutability),
+                extra_fields: constructor.extra_fields.clone(),
+                ..AbiEntry::new("constructor")
+            });
+        }
+
+        for f in &self.functions {
+            entries.push(AbiEntry {
+                name: Some(f.name.clone()),
+                inputs: Some(f.inputs.clone()),
+                outputs: Some(f.outputs.clone()),
+                state_mutability: Some(f.state_mutability),
+                extra_fields: f.extra_fields.clone(),
+                ..AbiEntry::new("function")
+            });
+        }
+
+        for e in &self.events {
+            entries.push(AbiEntry {
+                name: Some(e.name.clone()),
+                inputs: Some(e.inputs.clone()),
+                anonymous: Some(e.anonymous),
+                extra_fields: e.extra_fields.clone(),
+                ..AbiEntry::new("event")
+            });
+        }
+
+        for e in &self.errors {
+            entries.push(AbiEntry {
+                name: Some(e.name.clone()),
+                inputs: Some(e.inputs.clone()),
+                extra_fields: e.extra_fields.clone(),
+                ..AbiEntry::new("error")
+            });
+        }
+
+        if let Some(receive) = &self.receive {
+            entries.push(AbiEntry {
+                state_mutability: Some(receive.state_mutability),
+                extra_fields: receive.extra_fields.clone(),
+                ..AbiEntry::new("receive")
+            });
+        }
+
+        if let Some(fallback) = &self.fallback {
+            entries.push(AbiEntry {
+                state_mutability: Some(fallback.state_mutability),
+                extra_fields: fallback.extra_fields.clone(),
+                ..AbiEntry::new("fallback")
+            });
+        }
+
+        serializer.collect_seq(entries)
+    }
+}
+
 /// Contract constructor definition.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Constructor {
@@ -118,6 +577,50 @@ pub struct Constructor {
     pub inputs: Vec<Param>,
     /// Constructor state mutability kind.
     pub state_mutability: StateMutability,
+    /// Unrecognised JSON ABI entry fields.
+    pub extra_fields: ExtraFields,
+}
+
+impl Constructor {
+    /// Encode deployment data, i.e. the contract creation bytecode followed by
+    /// the ABI encoded constructor arguments.
+    pub fn encode_input(&self, bytecode: &[u8], values: &[Value]) -> Result<Vec<u8>> {
+        check_input_values(&self.inputs, values)?;
+
+        let mut buf = bytecode.to_vec();
+        buf.extend(Value::encode(values));
+
+        Ok(buf)
+    }
+
+    /// Decode constructor arguments from a deployment transaction input, given
+    /// the length of the contract creation bytecode that precedes them.
+    pub fn decode_input_from_deployment(
+        &self,
+        creation_code_len: usize,
+        tx_input: &[u8],
+    ) -> Result<DecodedParams> {
+        let input = tx_input
+            .get(creation_code_len..)
+            .ok_or(Error::InputTooShort {
+                needed: creation_code_len,
+                at: 0,
+            })?;
+
+        DecodedParams::decode_from_slice(&self.inputs, input, &DecodeOptions::default())
+    }
+}
+
+impl std::fmt::Display for Constructor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "constructor({})", join_params(&self.inputs))?;
+
+        if self.state_mutability != StateMutability::NonPayable {
+            write!(f, " {}", self.state_mutability)?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Contract function definition.
@@ -131,17 +634,14 @@ pub struct Function {
     pub outputs: Vec<Param>,
     /// Function state mutability kind.
     pub state_mutability: StateMutability,
+    /// Unrecognised JSON ABI entry fields.
+    pub extra_fields: ExtraFields,
 }
 
 impl Function {
     /// Computes the function's method id (function selector).
     pub fn method_id(&self) -> [u8; 4] {
-        use tiny_keccak::{Hasher, Keccak};
-
-        let mut keccak_out = [0u8; 32];
-        let mut hasher = Keccak::v256();
-        hasher.update(self.signature().as_bytes());
-        hasher.finalize(&mut keccak_out);
+        let keccak_out = keccak256(self.signature().as_bytes());
 
         let mut mid = [0u8; 4];
         mid.copy_from_slice(&keccak_out[0..4]);
@@ -164,24 +664,208 @@ impl Function {
 
     // Decode function input from slice.
     pub fn decode_input_from_slice(&self, input: &[u8]) -> Result<DecodedParams> {
-        let inputs_types = self
-            .inputs
-            .iter()
-            .map(|f_input| f_input.type_.clone())
-            .collect::<Vec<_>>();
+        self.decode_input_from_slice_with(input, &DecodeOptions::default())
+    }
+
+    /// Decode function input from slice using the given decode options.
+    pub fn decode_input_from_slice_with(
+        &self,
+        input: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<DecodedParams> {
+        DecodedParams::decode_from_slice(&self.inputs, input, options)
+    }
+
+    /// Decode function output (return data) from slice.
+    pub fn decode_output_from_slice(&self, output: &[u8]) -> Result<DecodedParams> {
+        self.decode_output_from_slice_with(output, &DecodeOptions::default())
+    }
+
+    /// Decode function output (return data) from slice using the given decode options.
+    pub fn decode_output_from_slice_with(
+        &self,
+        output: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<DecodedParams> {
+        DecodedParams::decode_from_slice(&self.outputs, output, options)
+    }
+
+    /// Decode function output (return data) from hex string.
+    pub fn decode_output_from_hex(&self, output: &str) -> Result<DecodedParams> {
+        let slice = hex::decode(output)?;
+
+        self.decode_output_from_slice(&slice)
+    }
+
+    /// Encode function input prefixed with the function's method id.
+    pub fn encode_input(&self, values: &[Value]) -> Result<Vec<u8>> {
+        check_input_values(&self.inputs, values)?;
+
+        let mut buf = self.method_id().to_vec();
+        buf.extend(Value::encode(values));
+
+        Ok(buf)
+    }
+}
+
+impl std::fmt::Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "function {}({})", self.name, join_params(&self.inputs))?;
+
+        if self.state_mutability != StateMutability::NonPayable {
+            write!(f, " {}", self.state_mutability)?;
+        }
+
+        if !self.outputs.is_empty() {
+            write!(f, " returns ({})", join_params(&self.outputs))?;
+        }
+
+        Ok(())
+    }
+}
 
-        Ok(DecodedParams::from(
+/// Contract custom error definition.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AbiError {
+    /// Error name.
+    pub name: String,
+    /// Error inputs.
+    pub inputs: Vec<Param>,
+    /// Unrecognised JSON ABI entry fields.
+    pub extra_fields: ExtraFields,
+}
+
+impl AbiError {
+    /// Computes the error's selector.
+    pub fn selector(&self) -> [u8; 4] {
+        let keccak_out = keccak256(self.signature().as_bytes());
+
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&keccak_out[0..4]);
+
+        selector
+    }
+
+    /// Returns the error's signature.
+    pub fn signature(&self) -> String {
+        format!(
+            "{}({})",
+            self.name,
             self.inputs
                 .iter()
-                .cloned()
-                .zip(Value::decode_from_slice(input, &inputs_types)?)
-                .collect::<Vec<_>>(),
-        ))
+                .map(|param| param.type_.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    }
+
+    /// Decode error arguments from slice (revert data without the selector).
+    pub fn decode_input_from_slice(&self, input: &[u8]) -> Result<DecodedParams> {
+        self.decode_input_from_slice_with(input, &DecodeOptions::default())
+    }
+
+    /// Decode error arguments from slice using the given decode options.
+    pub fn decode_input_from_slice_with(
+        &self,
+        input: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<DecodedParams> {
+        DecodedParams::decode_from_slice(&self.inputs, input, options)
     }
 }
 
+impl std::fmt::Display for AbiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error {}({})", self.name, join_params(&self.inputs))
+    }
+}
+
+// Checks that the given values match the given input params.
+pub(crate) fn check_input_values(inputs: &[Param], values: &[Value]) -> Result<()> {
+    if values.len() != inputs.len() {
+        return Err(Error::InvalidValue(format!(
+            "expected {} inputs, got {}",
+            inputs.len(),
+            values.len()
+        )));
+    }
+
+    for (param, value) in inputs.iter().zip(values) {
+        value.type_check(&param.type_).map_err(|e| match e {
+            Error::InvalidValue(msg) => {
+                Error::InvalidValue(format!("invalid value for input {}: {}", param.name, msg))
+            }
+            e => e,
+        })?;
+    }
+
+    Ok(())
+}
+
+// Reads the 4 bytes selector at the start of the given input.
+pub(crate) fn read_selector(input: &[u8]) -> Result<[u8; 4]> {
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(
+        input
+            .get(0..4)
+            .ok_or(Error::InputTooShort { needed: 4, at: 0 })?,
+    );
+
+    Ok(selector)
+}
+
+// Joins params in human-readable form.
+pub(crate) fn join_params(params: &[Param]) -> String {
+    params
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Contract receive function definition.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Receive {
+    /// Receive function state mutability kind (always payable for Solidity contracts).
+    pub state_mutability: StateMutability,
+    /// Unrecognised JSON ABI entry fields.
+    pub extra_fields: ExtraFields,
+}
+
+impl std::fmt::Display for Receive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "receive() external {}", self.state_mutability)
+    }
+}
+
+/// Contract fallback function definition.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Fallback {
+    /// Fallback function state mutability kind.
+    pub state_mutability: StateMutability,
+    /// Unrecognised JSON ABI entry fields.
+    pub extra_fields: ExtraFields,
+}
+
+impl std::fmt::Display for Fallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fallback() external")?;
+
+        if self.state_mutability != StateMutability::NonPayable {
+            write!(f, " {}", self.state_mutability)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Unrecognised fields of a JSON ABI entry (e.g. Vyper's `gas`), kept so that
+/// ABIs serialize back losslessly. The legacy `constant` and `payable` fields
+/// are kept here too, `stateMutability` is derived from them when missing.
+pub type ExtraFields = serde_json::Map<String, serde_json::Value>;
+
 /// Available state mutability values for functions and constructors.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum StateMutability {
     /// Specified to not read the blockchain state.
@@ -194,16 +878,72 @@ pub enum StateMutability {
     Payable,
 }
 
-#[derive(Debug, Deserialize)]
+impl std::fmt::Display for StateMutability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateMutability::Pure => write!(f, "pure"),
+            StateMutability::View => write!(f, "view"),
+            StateMutability::NonPayable => write!(f, "nonpayable"),
+            StateMutability::Payable => write!(f, "payable"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct AbiEntry {
-    #[serde(rename = "type")]
+    // legacy ABIs may omit the type of functions
+    #[serde(rename = "type", default = "AbiEntry::default_type")]
     type_: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     inputs: Option<Vec<Param>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     outputs: Option<Vec<Param>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     state_mutability: Option<StateMutability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     anonymous: Option<bool>,
+    // includes the legacy (pre solc 0.6) `constant` and `payable` fields
+    #[serde(flatten)]
+    extra_fields: ExtraFields,
+}
+
+impl AbiEntry {
+    fn new(type_: &str) -> Self {
+        Self {
+            type_: type_.to_string(),
+            name: None,
+            inputs: None,
+            outputs: None,
+            state_mutability: None,
+            anonymous: None,
+            extra_fields: ExtraFields::new(),
+        }
+    }
+
+    fn default_type() -> String {
+        "function".to_string()
+    }
+
+    // Returns the entry's state mutability, derived from the legacy `constant`
+    // and `payable` fields (both default to false) when it is missing.
+    fn state_mutability(&self) -> StateMutability {
+        let legacy = |field: &str| {
+            self.extra_fields
+                .get(field)
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or(false)
+        };
+
+        self.state_mutability
+            .unwrap_or(match (legacy("constant"), legacy("payable")) {
+                (true, _) => StateMutability::View,
+                (false, true) => StateMutability::Payable,
+                (false, false) => StateMutability::NonPayable,
+            })
+    }
 }
 
 struct AbiVisitor;
@@ -219,13 +959,7 @@ impl<'de> Visitor<'de> for AbiVisitor {
     where
         A: serde::de::SeqAccess<'de>,
     {
-        let mut abi = Abi {
-            constructor: None,
-            functions: vec![],
-            events: vec![],
-            has_receive: false,
-            has_fallback: false,
-        };
+        let mut abi = Abi::default();
 
         loop {
             let entry = seq.next_element::<AbiEntry>()?;
@@ -234,31 +968,40 @@ impl<'de> Visitor<'de> for AbiVisitor {
                 None => return Ok(abi),
 
                 Some(entry) => match entry.type_.as_str() {
-                    "receive" => abi.has_receive = true,
+                    "receive" => {
+                        // receive functions are payable by definition
+                        let state_mutability =
+                            entry.state_mutability.unwrap_or(StateMutability::Payable);
 
-                    "fallback" => abi.has_fallback = true,
+                        abi.receive = Some(Receive {
+                            state_mutability,
+                            extra_fields: entry.extra_fields,
+                        });
+                    }
+
+                    "fallback" => {
+                        let state_mutability = entry.state_mutability();
+
+                        abi.fallback = Some(Fallback {
+                            state_mutability,
+                            extra_fields: entry.extra_fields,
+                        });
+                    }
 
                     "constructor" => {
-                        let state_mutability = entry.state_mutability.ok_or_else(|| {
-                            serde::de::Error::custom(
-                                "missing constructor state mutability".to_string(),
-                            )
-                        })?;
+                        let state_mutability = entry.state_mutability();
 
                         let inputs = entry.inputs.unwrap_or_default();
 
                         abi.constructor = Some(Constructor {
                             inputs,
                             state_mutability,
+                            extra_fields: entry.extra_fields,
                         });
                     }
 
                     "function" => {
-                        let state_mutability = entry.state_mutability.ok_or_else(|| {
-                            serde::de::Error::custom(
-                                "missing function state mutability".to_string(),
-                            )
-                        })?;
+                        let state_mutability = entry.state_mutability();
 
                         let inputs = entry.inputs.unwrap_or_default();
 
@@ -273,6 +1016,7 @@ impl<'de> Visitor<'de> for AbiVisitor {
                             inputs,
                             outputs,
                             state_mutability,
+                            extra_fields: entry.extra_fields,
                         });
                     }
 
@@ -291,6 +1035,21 @@ impl<'de> Visitor<'de> for AbiVisitor {
                             name,
                             inputs,
                             anonymous,
+                            extra_fields: entry.extra_fields,
+                        });
+                    }
+
+                    "error" => {
+                        let inputs = entry.inputs.unwrap_or_default();
+
+                        let name = entry.name.ok_or_else(|| {
+                            serde::de::Error::custom("missing error name".to_string())
+                        })?;
+
+                        abi.errors.push(AbiError {
+                            name,
+                            inputs,
+                            extra_fields: entry.extra_fields,
                         });
                     }
 
@@ -313,7 +1072,7 @@ mod test {
 
     use ethereum_types::{H160, U256};
 
-    use crate::types::Type;
+    use crate::{types::Type, ComponentInternalTypes, InternalType};
 
     use super::*;
 
@@ -325,15 +1084,20 @@ mod test {
                     name: "".to_string(),
                     type_: Type::Address,
                     indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
                 },
                 Param {
                     name: "x".to_string(),
                     type_: Type::FixedArray(Box::new(Type::Uint(56)), 2),
                     indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
                 },
             ],
             outputs: vec![],
             state_mutability: StateMutability::Pure,
+            extra_fields: ExtraFields::new(),
         }
     }
 
@@ -368,8 +1132,8 @@ mod test {
             constructor: None,
             functions: vec![fun],
             events: vec![],
-            has_receive: false,
-            has_fallback: false,
+            errors: vec![],
+            ..Abi::default()
         };
 
         let mut enc_input = abi.functions[0].method_id().to_vec();
@@ -391,6 +1155,538 @@ mod test {
         assert_eq!(dec, (&abi.functions[0], expected_decoded_params));
     }
 
+    #[test]
+    fn function_encode_input() {
+        let addr = H160::random();
+
+        let input_values = vec![
+            Value::Address(addr),
+            Value::FixedArray(
+                vec![
+                    Value::Uint(U256::from(37), 56),
+                    Value::Uint(U256::from(109), 56),
+                ],
+                Type::Uint(56),
+            ),
+        ];
+
+        let fun = test_function();
+
+        let mut expected = fun.method_id().to_vec();
+        expected.extend(Value::encode(&input_values));
+
+        assert_eq!(fun.encode_input(&input_values).unwrap(), expected);
+
+        let abi = Abi {
+            constructor: None,
+            functions: vec![fun],
+            events: vec![],
+            errors: vec![],
+            ..Abi::default()
+        };
+
+        assert_eq!(
+            abi.encode_input("funname", &input_values).unwrap(),
+            expected
+        );
+        assert!(abi.encode_input("other", &input_values).is_err());
+    }
+
+    #[test]
+    fn function_encode_input_invalid_values() {
+        let fun = test_function();
+
+        assert!(fun.encode_input(&[Value::Address(H160::random())]).is_err());
+        assert!(fun
+            .encode_input(&[
+                Value::Address(H160::random()),
+                Value::FixedArray(vec![Value::Uint(U256::from(1), 56)], Type::Uint(56)),
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn abi_decode_output() {
+        let mut fun = test_function();
+        fun.outputs = vec![
+            Param {
+                name: "ok".to_string(),
+                type_: Type::Bool,
+                indexed: None,
+                internal_type: None,
+                component_internal_types: None,
+            },
+            Param {
+                name: "s".to_string(),
+                type_: Type::String,
+                indexed: None,
+                internal_type: None,
+                component_internal_types: None,
+            },
+        ];
+
+        let output_values = vec![Value::Bool(true), Value::String("abc".to_string())];
+        let enc_output = Value::encode(&output_values);
+
+        let expected_decoded_params = DecodedParams::from(
+            fun.outputs
+                .iter()
+                .cloned()
+                .zip(output_values)
+                .collect::<Vec<(Param, Value)>>(),
+        );
+
+        assert_eq!(
+            fun.decode_output_from_hex(&hex::encode(&enc_output))
+                .expect("decode_output_from_hex failed"),
+            expected_decoded_params
+        );
+
+        let abi = Abi {
+            constructor: None,
+            functions: vec![fun],
+            events: vec![],
+            errors: vec![],
+            ..Abi::default()
+        };
+
+        assert_eq!(
+            abi.decode_output("funname", &enc_output)
+                .expect("decode_output by name failed"),
+            (&abi.functions[0], expected_decoded_params.clone())
+        );
+        assert_eq!(
+            abi.decode_output("0x831fc720", &enc_output)
+                .expect("decode_output by selector failed"),
+            (&abi.functions[0], expected_decoded_params)
+        );
+        assert!(abi.decode_output("other", &enc_output).is_err());
+    }
+
+    #[test]
+    fn abi_error_selector() {
+        let err = AbiError {
+            name: "InsufficientBalance".to_string(),
+            inputs: vec![
+                Param {
+                    name: "available".to_string(),
+                    type_: Type::Uint(256),
+                    indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
+                },
+                Param {
+                    name: "required".to_string(),
+                    type_: Type::Uint(256),
+                    indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
+                },
+            ],
+            extra_fields: ExtraFields::new(),
+        };
+
+        assert_eq!(err.signature(), "InsufficientBalance(uint256,uint256)");
+        assert_eq!(err.selector(), [0xcf, 0x47, 0x91, 0x81]);
+    }
+
+    #[test]
+    fn abi_decode_error_from_slice() {
+        let s = r#"[{"inputs":[{"internalType":"uint256","name":"available","type":"uint256"},{"internalType":"uint256","name":"required","type":"uint256"}],"name":"InsufficientBalance","type":"error"}]"#;
+        let abi = Abi::from_str(s).unwrap();
+
+        assert_eq!(abi.errors.len(), 1);
+
+        let values = vec![
+            Value::Uint(U256::from(10), 256),
+            Value::Uint(U256::from(20), 256),
+        ];
+
+        let mut revert_data = abi.errors[0].selector().to_vec();
+        revert_data.extend(Value::encode(&values));
+
+        let expected_decoded_params = DecodedParams::from(
+            abi.errors[0]
+                .inputs
+                .iter()
+                .cloned()
+                .zip(values)
+                .collect::<Vec<(Param, Value)>>(),
+        );
+
+        assert_eq!(
+            abi.decode_error_from_slice(&revert_data)
+                .expect("decode_error_from_slice failed"),
+            (&abi.errors[0], expected_decoded_params)
+        );
+        assert!(abi.decode_error_from_slice(&[0u8; 4]).is_err());
+        assert!(abi.decode_error_from_slice(&[0u8; 2]).is_err());
+    }
+
+    #[test]
+    fn serialize_abi() {
+        let s = r#"[{"inputs":[{"internalType":"address","name":"a","type":"address"}],"stateMutability":"nonpayable","type":"constructor"},{"anonymous":false,"inputs":[{"indexed":true,"internalType":"address","name":"x","type":"address"},{"indexed":false,"internalType":"uint256","name":"y","type":"uint256"}],"name":"E","type":"event"},{"inputs":[{"internalType":"uint256","name":"x","type":"uint256"}],"name":"f","outputs":[{"internalType":"uint256","name":"","type":"uint256"}],"stateMutability":"view","type":"function"},{"inputs":[],"name":"Err","type":"error"},{"stateMutability":"payable","type":"receive"}]"#;
+        let abi = Abi::from_str(s).unwrap();
+
+        let v = serde_json::to_value(&abi).unwrap();
+
+        assert_eq!(
+            v,
+            serde_json::json!([
+                {
+                    "type": "constructor",
+                    "inputs": [{"name": "a", "type": "address", "internalType": "address"}],
+                    "stateMutability": "nonpayable"
+                },
+                {
+                    "type": "function",
+                    "name": "f",
+                    "inputs": [{"name": "x", "type": "uint256", "internalType": "uint256"}],
+                    "outputs": [{"name": "", "type": "uint256", "internalType": "uint256"}],
+                    "stateMutability": "view"
+                },
+                {
+                    "type": "event",
+                    "name": "E",
+                    "inputs": [
+                        {"name": "x", "type": "address", "indexed": true, "internalType": "address"},
+                        {"name": "y", "type": "uint256", "indexed": false, "internalType": "uint256"}
+                    ],
+                    "anonymous": false
+                },
+                {
+                    "type": "error",
+                    "name": "Err",
+                    "inputs": []
+                },
+                {
+                    "type": "receive",
+                    "stateMutability": "payable"
+                }
+            ])
+        );
+
+        assert_eq!(Abi::from_str(&v.to_string()).unwrap(), abi);
+    }
+
+    #[test]
+    fn from_human_readable() {
+        let abi = Abi::from_human_readable(&[
+            "constructor(address a)",
+            "event E(address x, uint256 y)",
+            "function f(uint256 x) returns (uint256)",
+            "receive() external payable",
+        ])
+        .unwrap();
+
+        let s = r#"[{"inputs":[{"name":"a","type":"address"}],"stateMutability":"nonpayable","type":"constructor"},{"anonymous":false,"inputs":[{"indexed":false,"name":"x","type":"address"},{"indexed":false,"name":"y","type":"uint256"}],"name":"E","type":"event"},{"inputs":[{"name":"x","type":"uint256"}],"name":"f","outputs":[{"name":"","type":"uint256"}],"stateMutability":"nonpayable","type":"function"},{"stateMutability":"payable","type":"receive"}]"#;
+
+        assert_eq!(abi, Abi::from_str(s).unwrap());
+        assert!(Abi::from_human_readable(&["function f(uint256 x"]).is_err());
+    }
+
+    #[test]
+    fn overloaded_functions() {
+        let abi = Abi::from_human_readable(&[
+            "function safeTransferFrom(address from, address to, uint256 id)",
+            "function safeTransferFrom(address from, address to, uint256 id, bytes data)",
+            "function approve(address to, uint256 id)",
+            "function totalSupply()",
+        ])
+        .unwrap();
+
+        assert_eq!(abi.function("approve").unwrap().name, "approve");
+        assert!(matches!(
+            abi.function("safeTransferFrom"),
+            Err(Error::AmbiguousFunction(_))
+        ));
+        assert!(matches!(
+            abi.function("transfer"),
+            Err(Error::UnknownFunction(_))
+        ));
+        assert_eq!(abi.functions_by_name("safeTransferFrom").len(), 2);
+
+        let f = abi
+            .function_by_signature("safeTransferFrom(address, address, uint, bytes)")
+            .unwrap();
+
+        assert_eq!(f, &abi.functions[1]);
+        assert_eq!(
+            abi.function_by_selector([0x42, 0x84, 0x2e, 0x0e]).unwrap(),
+            &abi.functions[0]
+        );
+        assert!(abi.function_by_signature("approve(address)").is_err());
+        assert_eq!(
+            abi.function_by_signature("totalSupply( )").unwrap(),
+            &abi.functions[3]
+        );
+        assert!(abi.function_by_signature("approve").is_err());
+
+        let values = vec![
+            Value::Address(H160::random()),
+            Value::Address(H160::random()),
+            Value::Uint(U256::from(1), 256),
+            Value::Bytes(vec![]),
+        ];
+
+        assert_eq!(
+            abi.encode_input("safeTransferFrom", &values).unwrap(),
+            abi.functions[1].encode_input(&values).unwrap()
+        );
+        assert_eq!(
+            abi.encode_input("safeTransferFrom", &values[..3]).unwrap(),
+            abi.functions[0].encode_input(&values[..3]).unwrap()
+        );
+        assert!(matches!(
+            abi.encode_input("safeTransferFrom", &values[..2]),
+            Err(Error::InvalidValue(_))
+        ));
+
+        let (f, _) = abi
+            .decode_output("0x42842e0e", &[])
+            .expect("decode_output failed");
+
+        assert_eq!(f, &abi.functions[0]);
+    }
+
+    #[test]
+    fn abi_index() {
+        let mut abi = Abi::from_human_readable(&[
+            "function f(uint256 x)",
+            "event E(uint256 indexed x)",
+            "error Unauthorized()",
+        ])
+        .unwrap();
+
+        let selector = abi.functions[0].method_id();
+        let topic = abi.events[0].topic();
+
+        assert_eq!(abi.function_by_selector(selector).unwrap().name, "f");
+        assert_eq!(
+            abi.decode_log_from_slice(&[topic, H256::zero()], &[])
+                .unwrap()
+                .0
+                .name,
+            "E"
+        );
+        assert_eq!(
+            abi.decode_error_from_slice(&abi.errors[0].selector())
+                .unwrap()
+                .0
+                .name,
+            "Unauthorized"
+        );
+
+        let index = abi.index();
+
+        assert_eq!(index.function_by_selector(selector).unwrap().name, "f");
+        assert_eq!(index.event_by_topic(&topic).unwrap().name, "E");
+        assert_eq!(
+            index
+                .decode_error_from_slice(&abi.errors[0].selector())
+                .unwrap()
+                .0
+                .name,
+            "Unauthorized"
+        );
+        assert!(index.function_by_selector([0; 4]).is_err());
+
+        // lookups reflect changes to the ABI
+        let g = Function {
+            name: "g".to_string(),
+            ..abi.functions[0].clone()
+        };
+        let g_selector = g.method_id();
+        abi.functions.insert(0, g);
+
+        assert_eq!(abi.function_by_selector(g_selector).unwrap().name, "g");
+        assert_eq!(abi.function_by_selector(selector).unwrap().name, "f");
+
+        let input = abi.functions[1]
+            .encode_input(&[Value::Uint(U256::from(1), 256)])
+            .unwrap();
+        assert_eq!(abi.decode_input_from_slice(&input).unwrap().0.name, "f");
+        assert_eq!(
+            abi.index().decode_input_from_slice(&input).unwrap().0.name,
+            "f"
+        );
+    }
+
+    #[test]
+    fn constructor_encode_input() {
+        let constructor = Constructor {
+            inputs: vec![
+                Param {
+                    name: "owner".to_string(),
+                    type_: Type::Address,
+                    indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
+                },
+                Param {
+                    name: "symbol".to_string(),
+                    type_: Type::String,
+                    indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
+                },
+            ],
+            state_mutability: StateMutability::NonPayable,
+            extra_fields: ExtraFields::new(),
+        };
+
+        let bytecode = hex::decode("6080604052348015600f57600080fd5b50").unwrap();
+        let values = vec![
+            Value::Address(H160::random()),
+            Value::String("ABC".to_string()),
+        ];
+
+        let deployment = constructor.encode_input(&bytecode, &values).unwrap();
+
+        assert_eq!(&deployment[..bytecode.len()], &bytecode[..]);
+        assert_eq!(&deployment[bytecode.len()..], &Value::encode(&values)[..]);
+
+        assert_eq!(
+            constructor
+                .decode_input_from_deployment(bytecode.len(), &deployment)
+                .expect("decode_input_from_deployment failed"),
+            DecodedParams::from(
+                constructor
+                    .inputs
+                    .iter()
+                    .cloned()
+                    .zip(values)
+                    .collect::<Vec<(Param, Value)>>()
+            )
+        );
+
+        assert!(constructor
+            .decode_input_from_deployment(deployment.len() + 1, &deployment)
+            .is_err());
+        assert!(constructor
+            .encode_input(&bytecode, &[Value::Bool(true)])
+            .is_err());
+    }
+
+    #[test]
+    fn to_human_readable() {
+        let fragments = vec![
+            "constructor(address a) payable",
+            "function f(uint256 x, tuple(address to, bytes[] data)[2] calls) view returns (uint256, bool ok)",
+            "function g()",
+            "event E(address indexed x, uint256 y) anonymous",
+            "error Err(string reason)",
+            "receive() external payable",
+            "fallback() external",
+        ];
+
+        let abi = Abi::from_human_readable(&fragments).unwrap();
+
+        assert_eq!(abi.to_human_readable(), fragments);
+        assert_eq!(
+            Abi::from_human_readable(&abi.to_human_readable()).unwrap(),
+            abi
+        );
+    }
+
+    #[test]
+    fn abi_decode_errors() {
+        let abi = Abi {
+            constructor: None,
+            functions: vec![test_function()],
+            events: vec![],
+            errors: vec![],
+            ..Abi::default()
+        };
+
+        assert!(matches!(
+            abi.decode_input_from_slice(&[0x83, 0x1f]),
+            Err(Error::InputTooShort { needed: 4, at: 0 })
+        ));
+        assert!(matches!(
+            abi.decode_input_from_slice(&[1, 2, 3, 4]),
+            Err(Error::UnknownSelector([1, 2, 3, 4]))
+        ));
+        assert!(matches!(
+            abi.decode_input_from_slice(&[0x83, 0x1f, 0xc7, 0x20, 0]),
+            Err(Error::InputTooShort { needed: 32, at: 0 })
+        ));
+        assert!(matches!(
+            abi.decode_log_from_slice(&[H256::zero()], &[]),
+            Err(Error::UnknownEventTopic(_))
+        ));
+        assert!(matches!(
+            abi.decode_input_from_hex("zz"),
+            Err(Error::Hex(_))
+        ));
+        assert!(matches!(Abi::from_str("{}"), Err(Error::Json(_))));
+    }
+
+    #[test]
+    fn legacy_abi() {
+        let s = r#"[
+            {"inputs":[],"payable":false,"type":"constructor"},
+            {"constant":true,"inputs":[],"name":"owner","outputs":[{"name":"","type":"address"}],"payable":false,"type":"function"},
+            {"constant":false,"inputs":[],"name":"deposit","outputs":[],"payable":true,"type":"function"},
+            {"constant":false,"inputs":[{"name":"x","type":"uint256"}],"name":"set","outputs":[]},
+            {"payable":true,"type":"fallback"}
+        ]"#;
+        let abi = Abi::from_str(s).unwrap();
+
+        assert_eq!(
+            abi.constructor.unwrap().state_mutability,
+            StateMutability::NonPayable
+        );
+        assert_eq!(
+            abi.functions
+                .iter()
+                .map(|f| (f.name.as_str(), f.state_mutability))
+                .collect::<Vec<_>>(),
+            vec![
+                ("owner", StateMutability::View),
+                ("deposit", StateMutability::Payable),
+                ("set", StateMutability::NonPayable),
+            ]
+        );
+        assert_eq!(
+            abi.fallback.unwrap().state_mutability,
+            StateMutability::Payable
+        );
+
+        // solc 0.5 ABIs have both the legacy fields and `stateMutability`
+        let s = r#"[{"inputs":[],"payable":true,"stateMutability":"payable","type":"constructor"},{"constant":true,"inputs":[],"name":"owner","outputs":[{"internalType":"address","name":"","type":"address"}],"payable":false,"stateMutability":"view","type":"function"},{"payable":true,"stateMutability":"payable","type":"fallback"}]"#;
+        let abi = Abi::from_str(s).unwrap();
+
+        assert_eq!(abi.functions[0].state_mutability, StateMutability::View);
+        assert_eq!(
+            serde_json::to_value(&abi).unwrap(),
+            serde_json::from_str::<serde_json::Value>(s).unwrap()
+        );
+    }
+
+    #[test]
+    fn extra_fields_round_trip() {
+        let s = r#"[{"inputs":[],"name":"f","outputs":[],"stateMutability":"view","type":"function","gas":2392},{"stateMutability":"payable","type":"fallback","x-note":{"a":[1]}}]"#;
+        let abi = Abi::from_str(s).unwrap();
+
+        assert_eq!(
+            abi.functions[0].extra_fields["gas"],
+            serde_json::json!(2392)
+        );
+        assert_eq!(
+            abi.fallback.as_ref().unwrap().state_mutability,
+            StateMutability::Payable
+        );
+        assert_eq!(
+            abi.to_human_readable(),
+            vec!["function f() view", "fallback() external payable"]
+        );
+
+        let v: serde_json::Value = serde_json::from_str(s).unwrap();
+
+        assert_eq!(serde_json::to_value(&abi).unwrap(), v);
+    }
+
     #[test]
     fn works_v1() {
         let s = r#"[{"inputs":[{"internalType":"address","name":"a","type":"address"}],"stateMutability":"nonpayable","type":"constructor"},{"anonymous":false,"inputs":[{"indexed":false,"internalType":"address","name":"x","type":"address"},{"indexed":false,"internalType":"uint256","name":"y","type":"uint256"}],"name":"E","type":"event"},{"inputs":[{"internalType":"uint256","name":"x","type":"uint256"}],"name":"f","outputs":[{"internalType":"uint256","name":"","type":"uint256"}],"stateMutability":"nonpayable","type":"function"},{"stateMutability":"payable","type":"receive"}]"#;
@@ -403,23 +1699,31 @@ mod test {
                     inputs: vec![Param {
                         name: "a".to_string(),
                         type_: Type::Address,
-                        indexed: None
+                        indexed: None,
+                        internal_type: Some(InternalType::Other("address".to_string())),
+                        component_internal_types: None,
                     }],
-                    state_mutability: StateMutability::NonPayable
+                    state_mutability: StateMutability::NonPayable,
+                    extra_fields: ExtraFields::new(),
                 }),
                 functions: vec![Function {
                     name: "f".to_string(),
                     inputs: vec![Param {
                         name: "x".to_string(),
                         type_: Type::Uint(256),
-                        indexed: None
+                        indexed: None,
+                        internal_type: Some(InternalType::Other("uint256".to_string())),
+                        component_internal_types: None,
                     }],
                     outputs: vec![Param {
                         name: "".to_string(),
                         type_: Type::Uint(256),
-                        indexed: None
+                        indexed: None,
+                        internal_type: Some(InternalType::Other("uint256".to_string())),
+                        component_internal_types: None,
                     }],
-                    state_mutability: StateMutability::NonPayable
+                    state_mutability: StateMutability::NonPayable,
+                    extra_fields: ExtraFields::new(),
                 }],
                 events: vec![Event {
                     name: "E".to_string(),
@@ -427,18 +1731,27 @@ mod test {
                         Param {
                             name: "x".to_string(),
                             type_: Type::Address,
-                            indexed: Some(false)
+                            indexed: Some(false),
+                            internal_type: Some(InternalType::Other("address".to_string())),
+                            component_internal_types: None,
                         },
                         Param {
                             name: "y".to_string(),
                             type_: Type::Uint(256),
-                            indexed: Some(false)
+                            indexed: Some(false),
+                            internal_type: Some(InternalType::Other("uint256".to_string())),
+                            component_internal_types: None,
                         }
                     ],
-                    anonymous: false
+                    anonymous: false,
+                    extra_fields: ExtraFields::new(),
                 }],
-                has_receive: true,
-                has_fallback: false
+                errors: vec![],
+                receive: Some(Receive {
+                    state_mutability: StateMutability::Payable,
+                    extra_fields: ExtraFields::new(),
+                }),
+                ..Abi::default()
             }
         )
     }
@@ -480,6 +1793,11 @@ mod test {
 
         let abi = Abi::from_str(&v.to_string()).unwrap();
 
+        let x_ty = Type::Tuple(vec![
+            ("a".to_string(), Type::Uint(256)),
+            ("b".to_string(), Type::String),
+        ]);
+
         assert_eq!(
             abi,
             Abi {
@@ -491,23 +1809,39 @@ mod test {
                             name: "n".to_string(),
                             type_: Type::Uint(256),
                             indexed: None,
+                            internal_type: Some(InternalType::Other("uint256".to_string())),
+                            component_internal_types: None,
                         },
                         Param {
                             name: "x".to_string(),
-                            type_: Type::Tuple(vec![
-                                ("a".to_string(), Type::Uint(256)),
-                                ("b".to_string(), Type::String)
-                            ]),
+                            type_: x_ty.clone(),
                             indexed: None,
+                            internal_type: Some(InternalType::Struct {
+                                contract: Some("A".to_string()),
+                                name: "X".to_string(),
+                            }),
+                            component_internal_types: Some(
+                                ComponentInternalTypes::new(
+                                    &x_ty,
+                                    vec![
+                                        (Some(InternalType::Other("uint256".to_string())), None),
+                                        (Some(InternalType::Other("string".to_string())), None),
+                                    ]
+                                )
+                                .unwrap()
+                            ),
                         }
                     ],
                     outputs: vec![],
                     state_mutability: StateMutability::NonPayable,
+                    extra_fields: ExtraFields::new(),
                 }],
                 events: vec![],
-                has_receive: false,
-                has_fallback: false,
+                errors: vec![],
+                ..Abi::default()
             }
         );
+
+        assert_eq!(serde_json::to_value(&abi).unwrap(), v);
     }
 }
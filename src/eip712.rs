@@ -0,0 +1,463 @@
+//! EIP-712 typed structured data hashing.
+//!
+//! ```
+//! use std::str::FromStr;
+//! use ethereum_abi::eip712::TypedData;
+//!
+//! let typed_data = TypedData::from_str(r#"{
+//!     "types": {
+//!         "EIP712Domain": [{"name": "name", "type": "string"}],
+//!         "Greeting": [{"name": "text", "type": "string"}]
+//!     },
+//!     "primaryType": "Greeting",
+//!     "domain": {"name": "Example"},
+//!     "message": {"text": "hello"}
+//! }"#).unwrap();
+//!
+//! let digest = typed_data.signing_hash().unwrap();
+//! ```
+
+use std::{collections::HashMap, str::FromStr};
+
+use ethereum_types::{H160, H256, U256};
+use serde::Deserialize;
+
+use crate::{keccak256, values::twos_complement, Error, Result, Type, Value};
+
+/// Name of the EIP-712 domain struct type.
+pub const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// EIP-712 typed data document.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    /// Struct type definitions by name, including `EIP712Domain`.
+    pub types: HashMap<String, Vec<TypedDataField>>,
+    /// Name of the message's struct type.
+    pub primary_type: String,
+    /// Domain values.
+    pub domain: serde_json::Value,
+    /// Message values.
+    pub message: serde_json::Value,
+}
+
+/// Member definition of an EIP-712 struct type.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TypedDataField {
+    /// Member name.
+    pub name: String,
+    /// Member type, e.g. `uint256`, `Person` or `Person[]`.
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+impl TypedData {
+    /// Computes the final digest to be signed, i.e.
+    /// `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+    pub fn signing_hash(&self) -> Result<H256> {
+        let mut buf = vec![0x19, 0x01];
+        buf.extend(self.domain_separator()?.as_bytes());
+        buf.extend(
+            self.hash_struct(&self.primary_type, &self.message)?
+                .as_bytes(),
+        );
+
+        Ok(H256::from(keccak256(&buf)))
+    }
+
+    /// Computes the domain separator, i.e. `hashStruct(domain)`.
+    pub fn domain_separator(&self) -> Result<H256> {
+        self.hash_struct(DOMAIN_TYPE, &self.domain)
+    }
+
+    /// Computes `hashStruct` for the given struct type and data.
+    pub fn hash_struct(&self, type_name: &str, data: &serde_json::Value) -> Result<H256> {
+        let fields = self.fields(type_name)?;
+
+        let mut buf = self.type_hash(type_name)?.as_bytes().to_vec();
+
+        for field in fields {
+            let value = data.get(&field.name).ok_or_else(|| {
+                Error::InvalidValue(format!("missing value for {}.{}", type_name, field.name))
+            })?;
+
+            buf.extend(self.encode_field(&field.type_, value)?.as_bytes());
+        }
+
+        Ok(H256::from(keccak256(&buf)))
+    }
+
+    /// Computes `typeHash` for the given struct type, i.e. `keccak256(encodeType(type))`.
+    pub fn type_hash(&self, type_name: &str) -> Result<H256> {
+        Ok(H256::from(keccak256(
+            self.encode_type(type_name)?.as_bytes(),
+        )))
+    }
+
+    /// Computes `encodeType` for the given struct type, e.g.
+    /// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+    pub fn encode_type(&self, type_name: &str) -> Result<String> {
+        let mut deps = vec![];
+        self.collect_dependencies(type_name, &mut deps)?;
+
+        // the primary type comes first, followed by its dependencies sorted by name
+        deps.retain(|dep| dep != type_name);
+        deps.sort();
+        deps.insert(0, type_name.to_string());
+
+        deps.iter()
+            .map(|dep| {
+                Ok(format!(
+                    "{}({})",
+                    dep,
+                    self.fields(dep)?
+                        .iter()
+                        .map(|field| format!("{} {}", field.type_, field.name))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                ))
+            })
+            .collect()
+    }
+
+    fn fields(&self, type_name: &str) -> Result<&Vec<TypedDataField>> {
+        self.types
+            .get(type_name)
+            .ok_or_else(|| Error::TypeParse(type_name.to_string()))
+    }
+
+    fn collect_dependencies(&self, type_name: &str, deps: &mut Vec<String>) -> Result<()> {
+        if deps.iter().any(|dep| dep == type_name) {
+            return Ok(());
+        }
+
+        deps.push(type_name.to_string());
+
+        for field in self.fields(type_name)? {
+            let base_type = base_type_name(&field.type_);
+
+            if self.types.contains_key(base_type) {
+                self.collect_dependencies(base_type, deps)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Encodes a struct member into a 32 bytes word, as defined by `encodeData`.
+    fn encode_field(&self, type_name: &str, value: &serde_json::Value) -> Result<H256> {
+        if let Some(elem_type_name) = array_elem_type_name(type_name) {
+            let values = value.as_array().ok_or_else(|| {
+                Error::InvalidValue(format!("expected array value for type {}", type_name))
+            })?;
+
+            if let Some(size) = array_size(type_name) {
+                if values.len() != size {
+                    return Err(Error::InvalidValue(format!(
+                        "{} elements for type {}",
+                        values.len(),
+                        type_name
+                    )));
+                }
+            }
+
+            let mut buf = vec![];
+            for value in values {
+                buf.extend(self.encode_field(elem_type_name, value)?.as_bytes());
+            }
+
+            return Ok(H256::from(keccak256(&buf)));
+        }
+
+        if self.types.contains_key(type_name) {
+            return self.hash_struct(type_name, value);
+        }
+
+        let ty = Type::from_str(type_name)?;
+        let value = json_to_value(&ty, value)?;
+        value.type_check(&ty)?;
+
+        match value {
+            Value::String(s) => Ok(H256::from(keccak256(s.as_bytes()))),
+            Value::Bytes(bytes) => Ok(H256::from(keccak256(&bytes))),
+            value => Ok(H256::from_slice(&Value::encode(&[value]))),
+        }
+    }
+}
+
+impl FromStr for TypedData {
+    type Err = Error;
+
+    /// Parses a JSON typed data document from a string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_str(s)?)
+    }
+}
+
+// Returns the type name without array suffixes, e.g. `Person[2][]` becomes `Person`.
+fn base_type_name(type_name: &str) -> &str {
+    type_name.split('[').next().unwrap_or(type_name)
+}
+
+// Returns the element type name of an array type, e.g. `Person[2][]` becomes `Person[2]`.
+fn array_elem_type_name(type_name: &str) -> Option<&str> {
+    if !type_name.ends_with(']') {
+        return None;
+    }
+
+    type_name.rfind('[').map(|i| &type_name[..i])
+}
+
+// Returns the size of a fixed size array type, e.g. `Person[][2]` gives `2`.
+fn array_size(type_name: &str) -> Option<usize> {
+    let (_, suffix) = type_name.strip_suffix(']')?.rsplit_once('[')?;
+
+    suffix.parse().ok()
+}
+
+// Converts a JSON value into a value of the given atomic or dynamic type.
+fn json_to_value(ty: &Type, value: &serde_json::Value) -> Result<Value> {
+    let invalid = || Error::InvalidValue(format!("invalid value for type {}: {}", ty, value));
+
+    match ty {
+        Type::Uint(size) => Ok(Value::Uint(json_to_uint(value).ok_or_else(invalid)?, *size)),
+
+        Type::Int(size) => {
+            let (negative, abs) = match value {
+                serde_json::Value::String(s) if s.starts_with('-') => (
+                    true,
+                    json_to_uint(&serde_json::Value::String(s[1..].to_string())),
+                ),
+                serde_json::Value::Number(n) if n.as_i64().is_some_and(|n| n < 0) => {
+                    (true, n.as_i64().map(|n| U256::from(n.unsigned_abs())))
+                }
+                _ => (false, json_to_uint(value)),
+            };
+
+            let abs = abs.ok_or_else(invalid)?;
+
+            Ok(Value::Int(twos_complement(negative, abs), *size))
+        }
+
+        Type::Address => value
+            .as_str()
+            .and_then(|s| H160::from_str(s.trim_start_matches("0x")).ok())
+            .map(Value::Address)
+            .ok_or_else(invalid),
+
+        Type::Bool => value.as_bool().map(Value::Bool).ok_or_else(invalid),
+
+        Type::FixedBytes(size) => {
+            let bytes = json_to_bytes(value).ok_or_else(invalid)?;
+
+            if bytes.len() != *size {
+                return Err(invalid());
+            }
+
+            Ok(Value::FixedBytes(bytes))
+        }
+
+        Type::String => value
+            .as_str()
+            .map(|s| Value::String(s.to_string()))
+            .ok_or_else(invalid),
+
+        Type::Bytes => json_to_bytes(value).map(Value::Bytes).ok_or_else(invalid),
+
+        _ => Err(Error::Unsupported(format!("EIP-712 type {}", ty))),
+    }
+}
+
+// Parses an unsigned integer from a JSON number, decimal string or 0x prefixed hex string.
+fn json_to_uint(value: &serde_json::Value) -> Option<U256> {
+    match value {
+        serde_json::Value::Number(n) => n.as_u64().map(U256::from),
+        serde_json::Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => U256::from_str_radix(hex, 16).ok(),
+            None => U256::from_dec_str(s).ok(),
+        },
+        _ => None,
+    }
+}
+
+fn json_to_bytes(value: &serde_json::Value) -> Option<Vec<u8>> {
+    value
+        .as_str()
+        .and_then(|s| hex::decode(s.trim_start_matches("0x")).ok())
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn mail_typed_data() -> TypedData {
+        TypedData::from_str(
+            r#"{
+                "types": {
+                    "EIP712Domain": [
+                        {"name": "name", "type": "string"},
+                        {"name": "version", "type": "string"},
+                        {"name": "chainId", "type": "uint256"},
+                        {"name": "verifyingContract", "type": "address"}
+                    ],
+                    "Person": [
+                        {"name": "name", "type": "string"},
+                        {"name": "wallet", "type": "address"}
+                    ],
+                    "Mail": [
+                        {"name": "from", "type": "Person"},
+                        {"name": "to", "type": "Person"},
+                        {"name": "contents", "type": "string"}
+                    ]
+                },
+                "primaryType": "Mail",
+                "domain": {
+                    "name": "Ether Mail",
+                    "version": "1",
+                    "chainId": 1,
+                    "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+                },
+                "message": {
+                    "from": {
+                        "name": "Cow",
+                        "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+                    },
+                    "to": {
+                        "name": "Bob",
+                        "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"
+                    },
+                    "contents": "Hello, Bob!"
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn h256(s: &str) -> H256 {
+        H256::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn encode_type() {
+        let typed_data = mail_typed_data();
+
+        assert_eq!(
+            typed_data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            typed_data.type_hash("Mail").unwrap(),
+            h256("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2")
+        );
+    }
+
+    #[test]
+    fn hash_struct() {
+        let typed_data = mail_typed_data();
+
+        assert_eq!(
+            typed_data.domain_separator().unwrap(),
+            h256("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+        );
+        assert_eq!(
+            typed_data.hash_struct("Mail", &typed_data.message).unwrap(),
+            h256("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+        );
+        assert_eq!(
+            typed_data.signing_hash().unwrap(),
+            h256("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+        );
+    }
+
+    #[test]
+    fn encode_arrays() {
+        let mut typed_data = mail_typed_data();
+        typed_data.types.insert(
+            "Group".to_string(),
+            vec![
+                TypedDataField {
+                    name: "members".to_string(),
+                    type_: "Person[]".to_string(),
+                },
+                TypedDataField {
+                    name: "scores".to_string(),
+                    type_: "int8[2]".to_string(),
+                },
+            ],
+        );
+
+        assert_eq!(
+            typed_data.encode_type("Group").unwrap(),
+            "Group(Person[] members,int8[2] scores)Person(string name,address wallet)"
+        );
+
+        let person = typed_data.message["from"].clone();
+        let data = serde_json::json!({"members": [person.clone()], "scores": [-1, "2"]});
+
+        let mut scores = [0xffu8; 64];
+        scores[32..63].copy_from_slice(&[0u8; 31]);
+        scores[63] = 2;
+
+        let mut expected = typed_data.type_hash("Group").unwrap().as_bytes().to_vec();
+        expected.extend(
+            keccak256(
+                typed_data
+                    .hash_struct("Person", &person)
+                    .unwrap()
+                    .as_bytes(),
+            )
+            .iter(),
+        );
+        expected.extend(keccak256(&scores).iter());
+
+        assert_eq!(
+            typed_data.hash_struct("Group", &data).unwrap(),
+            H256::from(keccak256(&expected))
+        );
+    }
+
+    #[test]
+    fn invalid_values() {
+        let typed_data = mail_typed_data();
+
+        assert!(typed_data
+            .hash_struct("Unknown", &typed_data.message)
+            .is_err());
+        assert!(typed_data
+            .hash_struct("Person", &serde_json::json!({"name": "Cow"}))
+            .is_err());
+        assert!(typed_data
+            .hash_struct(
+                "Person",
+                &serde_json::json!({"name": "Cow", "wallet": true})
+            )
+            .is_err());
+
+        let out_of_range = [
+            ("int8", serde_json::json!("200")),
+            ("int8", serde_json::json!(-129)),
+            ("uint8", serde_json::json!(300)),
+            ("uint8[2]", serde_json::json!([1, 2, 3])),
+            ("uint8[2]", serde_json::json!([1])),
+            ("uint8[][2]", serde_json::json!([[1], [2], [3]])),
+        ];
+        for (type_name, value) in out_of_range.iter() {
+            assert!(matches!(
+                typed_data.encode_field(type_name, value),
+                Err(Error::InvalidValue(_))
+            ));
+        }
+
+        assert!(typed_data
+            .encode_field("int8", &serde_json::json!("-128"))
+            .is_ok());
+        assert!(typed_data
+            .encode_field("uint8", &serde_json::json!(255))
+            .is_ok());
+        assert!(typed_data
+            .encode_field("uint8[2][]", &serde_json::json!([[1, 2], [3, 4], [5, 6]]))
+            .is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+use ethereum_types::{H256, U256};
+
+/// Result type returned by this library.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors returned by this library.
+#[derive(Debug)]
+pub enum Error {
+    /// No ABI function or custom error matches the given selector.
+    UnknownSelector([u8; 4]),
+    /// No ABI function has the given name.
+    UnknownFunction(String),
+    /// More than one ABI function (overload) matches the given name.
+    AmbiguousFunction(String),
+    /// No ABI event matches the given topic.
+    UnknownEventTopic(H256),
+    /// Input ended before `needed` bytes could be read at offset `at`.
+    InputTooShort {
+        /// Number of bytes needed.
+        needed: usize,
+        /// Offset at which the bytes were to be read.
+        at: usize,
+    },
+    /// An offset or length word does not point inside the input.
+    InvalidOffset(U256),
+    /// Decoded string is not valid UTF-8.
+    InvalidUtf8(std::string::FromUtf8Error),
+    /// Number of log topics does not match the event definition.
+    TopicCountMismatch {
+        /// Number of topics expected by the event definition.
+        expected: usize,
+        /// Number of topics given.
+        got: usize,
+    },
+    /// A decode limit was exceeded, the limit's `DecodeOptions` field is given.
+    LimitExceeded(&'static str),
+    /// Input is not canonically encoded, rejected in strict decode mode.
+    NonCanonical(&'static str),
+    /// Invalid type string.
+    TypeParse(String),
+    /// Invalid human-readable ABI fragment.
+    FragmentParse(String),
+    /// Values do not match their declared types.
+    InvalidValue(String),
+    /// Operation not supported for the given type or value.
+    Unsupported(String),
+    /// Invalid hex string.
+    Hex(hex::FromHexError),
+    /// Invalid JSON document.
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownSelector(selector) => {
+                write!(f, "unknown selector 0x{}", hex::encode(selector))
+            }
+            Error::UnknownFunction(name) => write!(f, "unknown function {}", name),
+            Error::AmbiguousFunction(name) => write!(f, "ambiguous overloaded function {}", name),
+            Error::UnknownEventTopic(topic) => write!(f, "unknown event topic {:?}", topic),
+            Error::InputTooShort { needed, at } => write!(
+                f,
+                "reached end of input while reading {} bytes at offset {}",
+                needed, at
+            ),
+            Error::InvalidOffset(offset) => write!(f, "invalid offset or length {}", offset),
+            Error::InvalidUtf8(err) => write!(f, "invalid UTF-8 string: {}", err),
+            Error::TopicCountMismatch { expected, got } => {
+                write!(f, "expected {} log topics, got {}", expected, got)
+            }
+            Error::LimitExceeded(limit) => write!(f, "decode limit {} exceeded", limit),
+            Error::NonCanonical(reason) => write!(f, "non-canonical encoding: {}", reason),
+            Error::TypeParse(s) => write!(f, "invalid type: {}", s),
+            Error::FragmentParse(s) => write!(f, "invalid human-readable ABI fragment: {}", s),
+            Error::InvalidValue(msg) => write!(f, "invalid value: {}", msg),
+            Error::Unsupported(msg) => write!(f, "unsupported: {}", msg),
+            Error::Hex(err) => write!(f, "invalid hex string: {}", err),
+            Error::Json(err) => write!(f, "invalid JSON: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidUtf8(err) => Some(err),
+            Error::Hex(err) => Some(err),
+            Error::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::string::FromUtf8Error> for Error {
+    fn from(err: std::string::FromUtf8Error) -> Self {
+        Error::InvalidUtf8(err)
+    }
+}
+
+impl From<hex::FromHexError> for Error {
+    fn from(err: hex::FromHexError) -> Self {
+        Error::Hex(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
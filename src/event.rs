@@ -1,8 +1,10 @@
-use anyhow::{anyhow, Result};
 use ethereum_types::H256;
 use std::collections::VecDeque;
 
-use crate::{DecodedParams, Param, Type, Value};
+use crate::{
+    abi::{check_input_values, join_params},
+    keccak256, DecodeOptions, DecodedParams, Error, ExtraFields, Param, Result, Type, Value,
+};
 
 /// Contract event definition.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -13,6 +15,20 @@ pub struct Event {
     pub inputs: Vec<Param>,
     /// Whether the event is anonymous or not.
     pub anonymous: bool,
+    /// Unrecognised JSON ABI entry fields.
+    pub extra_fields: ExtraFields,
+}
+
+impl std::fmt::Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "event {}({})", self.name, join_params(&self.inputs))?;
+
+        if self.anonymous {
+            write!(f, " anonymous")?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Event {
@@ -31,34 +47,39 @@ impl Event {
 
     /// Compute the event's topic hash
     pub fn topic(&self) -> H256 {
-        use tiny_keccak::{Hasher, Keccak};
-
-        let mut keccak_out = [0u8; 32];
-        let mut hasher = Keccak::v256();
-        hasher.update(self.signature().as_bytes());
-        hasher.finalize(&mut keccak_out);
-
-        H256::from_slice(&keccak_out)
+        H256::from(keccak256(self.signature().as_bytes()))
     }
 
     /// Decode event params from a log's topics and data.
-    pub fn decode_data_from_slice(
+    pub fn decode_data_from_slice(&self, topics: &[H256], data: &[u8]) -> Result<DecodedParams> {
+        self.decode_data_from_slice_with(topics, data, &DecodeOptions::default())
+    }
+
+    /// Decode event params from a log's topics and data using the given decode options.
+    pub fn decode_data_from_slice_with(
         &self,
         mut topics: &[H256],
         data: &[u8],
+        options: &DecodeOptions,
     ) -> Result<DecodedParams> {
+        let expected = self.topics_count();
+        let got = topics.len();
+        let mismatch = || Error::TopicCountMismatch { expected, got };
+
+        if expected != got {
+            return Err(mismatch());
+        }
+
         // strip event topic from the topics array
         // so that we end up with only the values we
         // need to decode
         if !self.anonymous {
-            topics = topics
-                .get(1..)
-                .ok_or_else(|| anyhow!("missing event topic"))?;
+            topics = &topics[1..];
         }
 
         let mut topics_values: VecDeque<_> = VecDeque::from(topics.to_vec());
 
-        let mut data_values = VecDeque::from(Value::decode_from_slice(
+        let mut data_values = VecDeque::from(Value::decode_from_slice_with(
             data,
             &self
                 .inputs
@@ -66,45 +87,124 @@ impl Event {
                 .filter(|input| !input.indexed.unwrap_or(false))
                 .map(|input| input.type_.clone())
                 .collect::<Vec<_>>(),
+            options,
         )?);
 
         let mut decoded = vec![];
         for input in self.inputs.iter().cloned() {
             let decoded_value = if input.indexed.unwrap_or(false) {
-                let val = topics_values
-                    .pop_front()
-                    .ok_or_else(|| anyhow!("insufficient topics entries"))?;
+                let val = topics_values.pop_front().ok_or_else(mismatch)?;
 
                 let bytes = val.to_fixed_bytes().to_vec();
 
                 if Self::is_encoded_to_keccak(&input.type_) {
-                    Ok(Value::FixedBytes(bytes))
+                    Value::FixedBytes(bytes)
                 } else {
-                    Value::decode_from_slice(&bytes, &[input.type_.clone()])?
-                        .first()
-                        .ok_or_else(|| anyhow!("no value decoded from topics entry"))
-                        .map(Clone::clone)
+                    // one value is decoded per type
+                    Value::decode_from_slice_with(
+                        &bytes,
+                        std::slice::from_ref(&input.type_),
+                        options,
+                    )?
+                    .remove(0)
                 }
             } else {
                 data_values
                     .pop_front()
-                    .ok_or_else(|| anyhow!("insuficient data values"))
+                    .expect("one value is decoded per non-indexed input")
             };
 
-            decoded.push((input, decoded_value?));
+            decoded.push((input, decoded_value));
         }
 
         Ok(DecodedParams::from(decoded))
     }
 
+    /// Encode event params into a log's topics and data.
+    ///
+    /// Indexed params whose type is dynamic, an array or a tuple are stored in
+    /// topics as the keccak256 hash of their encoding.
+    pub fn encode_log(&self, values: &[Value]) -> Result<(Vec<H256>, Vec<u8>)> {
+        check_input_values(&self.inputs, values)?;
+
+        let mut topics = vec![];
+        if !self.anonymous {
+            topics.push(self.topic());
+        }
+
+        let mut data_values = vec![];
+        for (input, value) in self.inputs.iter().zip(values) {
+            if input.indexed.unwrap_or(false) {
+                let topic = if Self::is_encoded_to_keccak(&input.type_) {
+                    H256::from(keccak256(&Self::encode_topic_value(value, false)))
+                } else {
+                    H256::from_slice(&Value::encode(std::slice::from_ref(value)))
+                };
+
+                topics.push(topic);
+            } else {
+                data_values.push(value.clone());
+            }
+        }
+
+        if topics.len() > 4 {
+            return Err(Error::InvalidValue(format!(
+                "too many topics: {}",
+                topics.len()
+            )));
+        }
+
+        Ok((topics, Value::encode(&data_values)))
+    }
+
+    // Encodes an indexed value whose topic is its keccak256 hash. Top-level bytes
+    // and strings are not padded; nested values are always padded to 32 bytes and
+    // arrays and tuples are encoded in place, without offsets or lengths.
+    fn encode_topic_value(value: &Value, nested: bool) -> Vec<u8> {
+        match value {
+            Value::String(s) => Self::encode_topic_bytes(s.as_bytes(), nested),
+            Value::Bytes(bytes) => Self::encode_topic_bytes(bytes, nested),
+            Value::FixedArray(values, _) | Value::Array(values, _) => values
+                .iter()
+                .flat_map(|value| Self::encode_topic_value(value, true))
+                .collect(),
+            Value::Tuple(values) => values
+                .iter()
+                .flat_map(|(_, value)| Self::encode_topic_value(value, true))
+                .collect(),
+            _ => Value::encode(std::slice::from_ref(value)),
+        }
+    }
+
+    fn encode_topic_bytes(bytes: &[u8], pad: bool) -> Vec<u8> {
+        let mut buf = bytes.to_vec();
+
+        if pad {
+            buf.resize(Value::padded32_size(buf.len()), 0);
+        }
+
+        buf
+    }
+
+    // Number of topics in a log of this event, including the event topic.
+    fn topics_count(&self) -> usize {
+        let indexed = self
+            .inputs
+            .iter()
+            .filter(|input| input.indexed.unwrap_or(false))
+            .count();
+
+        if self.anonymous {
+            indexed
+        } else {
+            indexed + 1
+        }
+    }
+
     fn is_encoded_to_keccak(ty: &Type) -> bool {
         matches!(
             ty,
-            Type::FixedArray(_, _)
-            | Type::Array(_)
-            | Type::Bytes
-            | Type::String
-            | Type::Tuple(_)
+            Type::FixedArray(_, _) | Type::Array(_) | Type::Bytes | Type::String | Type::Tuple(_)
         )
     }
 }
@@ -128,14 +228,19 @@ mod test {
                     name: "x".to_string(),
                     type_: Type::Uint(56),
                     indexed: Some(true),
+                    internal_type: None,
+                    component_internal_types: None,
                 },
                 Param {
                     name: "y".to_string(),
                     type_: Type::String,
                     indexed: Some(true),
+                    internal_type: None,
+                    component_internal_types: None,
                 },
             ],
             anonymous: false,
+            extra_fields: ExtraFields::new(),
         }
     }
 
@@ -146,6 +251,16 @@ mod test {
         assert_eq!(evt.signature(), "Approve(uint56,string)");
     }
 
+    #[test]
+    fn test_display() {
+        let evt = test_event();
+
+        assert_eq!(
+            evt.to_string(),
+            "event Approve(uint56 indexed x, string indexed y)"
+        );
+    }
+
     #[test]
     fn test_topic() {
         let evt = test_event();
@@ -157,6 +272,131 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_encode_log() {
+        let evt = test_event();
+
+        let (topics, data) = evt
+            .encode_log(&[
+                Value::Uint(U256::from(10), 56),
+                Value::String("abc".to_string()),
+            ])
+            .expect("encode_log failed");
+
+        assert_eq!(
+            topics,
+            vec![
+                evt.topic(),
+                H256::from_low_u64_be(10),
+                H256::from(keccak256(b"abc")),
+            ]
+        );
+        assert!(data.is_empty());
+
+        assert_eq!(
+            evt.decode_data_from_slice(&topics, &data).unwrap(),
+            DecodedParams::from(vec![
+                (evt.inputs[0].clone(), Value::Uint(U256::from(10), 56)),
+                (
+                    evt.inputs[1].clone(),
+                    Value::FixedBytes(keccak256(b"abc").to_vec())
+                ),
+            ])
+        );
+
+        assert!(evt.encode_log(&[Value::Uint(U256::from(10), 56)]).is_err());
+    }
+
+    #[test]
+    fn test_encode_log_data() {
+        let evt = Event {
+            name: "E".to_string(),
+            inputs: vec![
+                Param {
+                    name: "a".to_string(),
+                    type_: Type::Array(Box::new(Type::Bytes)),
+                    indexed: Some(true),
+                    internal_type: None,
+                    component_internal_types: None,
+                },
+                Param {
+                    name: "b".to_string(),
+                    type_: Type::String,
+                    indexed: Some(false),
+                    internal_type: None,
+                    component_internal_types: None,
+                },
+            ],
+            anonymous: true,
+            extra_fields: ExtraFields::new(),
+        };
+
+        let values = vec![
+            Value::Array(
+                vec![Value::Bytes(vec![1, 2]), Value::Bytes(vec![3])],
+                Type::Bytes,
+            ),
+            Value::String("abc".to_string()),
+        ];
+
+        let (topics, data) = evt.encode_log(&values).expect("encode_log failed");
+
+        let mut preimage = [0u8; 64];
+        preimage[0..2].copy_from_slice(&[1, 2]);
+        preimage[32] = 3;
+
+        assert_eq!(topics, vec![H256::from(keccak256(&preimage))]);
+        assert_eq!(data, Value::encode(&values[1..]));
+    }
+
+    #[test]
+    fn test_decode_topic_count_mismatch() {
+        let evt = test_event();
+
+        let res = evt.decode_data_from_slice(&[evt.topic(), H256::zero()], &[]);
+
+        assert!(matches!(
+            res,
+            Err(Error::TopicCountMismatch {
+                expected: 3,
+                got: 2
+            })
+        ));
+
+        // extra topics are rejected too
+        let res = evt.decode_data_from_slice(
+            &[evt.topic(), H256::zero(), H256::zero(), H256::zero()],
+            &[],
+        );
+
+        assert!(matches!(
+            res,
+            Err(Error::TopicCountMismatch {
+                expected: 3,
+                got: 4
+            })
+        ));
+
+        // missing data reports where it should have been read
+        let topics = [evt.topic(), H256::zero(), H256::zero()];
+        let evt = Event {
+            inputs: vec![
+                evt.inputs[0].clone(),
+                evt.inputs[1].clone(),
+                Param {
+                    indexed: Some(false),
+                    ..evt.inputs[0].clone()
+                },
+            ],
+            ..evt
+        };
+
+        assert!(matches!(
+            evt.decode_data_from_slice(&topics, &[0; 16]),
+            Err(Error::InputTooShort { needed: 32, at: 0 })
+        ));
+    }
+
     #[test]
     fn test_decode_data_from_slice() {
         let topics: Vec<_> = [
@@ -174,41 +414,47 @@ mod test {
             name: "x".to_string(),
             type_: Type::Uint(256),
             indexed: None,
+            internal_type: None,
+            component_internal_types: None,
         };
         let y = Param {
             name: "y".to_string(),
             type_: Type::Uint(256),
             indexed: Some(true),
+            internal_type: None,
+            component_internal_types: None,
         };
         let x1 = Param {
             name: "x1".to_string(),
             type_: Type::Uint(256),
             indexed: None,
+            internal_type: None,
+            component_internal_types: None,
         };
         let y1 = Param {
             name: "y1".to_string(),
             type_: Type::Uint(256),
             indexed: Some(true),
+            internal_type: None,
+            component_internal_types: None,
         };
         let s = Param {
             name: "s".to_string(),
             type_: Type::String,
             indexed: None,
+            internal_type: None,
+            component_internal_types: None,
         };
 
         let evt = Event {
             name: "Test".to_string(),
             inputs: vec![x.clone(), y.clone(), x1.clone(), y1.clone(), s.clone()],
             anonymous: false,
+            extra_fields: ExtraFields::new(),
         };
 
-        let abi = Abi {
-            constructor: None,
-            functions: vec![],
-            events: vec![evt],
-            has_receive: false,
-            has_fallback: false,
-        };
+        let mut abi = Abi::default();
+        abi.events.push(evt);
 
         assert_eq!(
             abi.decode_log_from_slice(&topics, &data)
@@ -225,4 +471,44 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn test_decode_strict_topic() {
+        let addr = Param {
+            name: "from".to_string(),
+            type_: Type::Address,
+            indexed: Some(true),
+            internal_type: None,
+            component_internal_types: None,
+        };
+
+        let evt = Event {
+            name: "Ping".to_string(),
+            inputs: vec![addr.clone()],
+            anonymous: false,
+            extra_fields: ExtraFields::new(),
+        };
+
+        let dirty =
+            H256::from_str("ff00000000000000000000000102030405060708090a0b0c0d0e0f1011121314")
+                .unwrap();
+        let topics = [evt.topic(), dirty];
+
+        let strict = DecodeOptions {
+            strict: true,
+            ..DecodeOptions::default()
+        };
+
+        assert!(matches!(
+            evt.decode_data_from_slice_with(&topics, &[], &strict),
+            Err(Error::NonCanonical(_))
+        ));
+        assert_eq!(
+            evt.decode_data_from_slice(&topics, &[]).unwrap(),
+            DecodedParams::from(vec![(
+                addr,
+                Value::Address(ethereum_types::H160::from_slice(&dirty.as_bytes()[12..]))
+            )])
+        );
+    }
 }
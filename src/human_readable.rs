@@ -0,0 +1,456 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while, take_while1},
+    character::complete::{char, multispace0, multispace1},
+    combinator::{opt, recognize, verify},
+    multi::{many0, separated_list0},
+    sequence::{delimited, pair, preceded, tuple},
+};
+
+use crate::{
+    params::{array_type, parse_array_sizes, parse_inline_type, TypeParseError, TypeParseResult},
+    AbiError, Constructor, Error, Event, ExtraFields, Fallback, Function, Param, Receive, Result,
+    StateMutability, Type,
+};
+
+/// Human-readable ABI fragment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Fragment {
+    Constructor(Constructor),
+    Function(Function),
+    Event(Event),
+    Error(AbiError),
+    Receive(Receive),
+    Fallback(Fallback),
+}
+
+/// Parses a single human-readable ABI fragment, e.g.
+/// `function transfer(address to, uint256 amount) external returns (bool)`.
+pub(crate) fn parse_fragment(input: &str) -> Result<Fragment> {
+    let input = input.trim();
+
+    match parse_any_fragment(input) {
+        Ok(("", fragment)) => Ok(fragment),
+        _ => Err(Error::FragmentParse(input.to_string())),
+    }
+}
+
+fn parse_any_fragment(input: &str) -> TypeParseResult<&str, Fragment> {
+    alt((
+        parse_function,
+        parse_event,
+        parse_error,
+        parse_constructor,
+        parse_receive,
+        parse_fallback,
+    ))(input)
+}
+
+fn parse_function(input: &str) -> TypeParseResult<&str, Fragment> {
+    let (i, _) = tag("function")(input)?;
+    let (i, name) = preceded(multispace1, parse_identifier)(i)?;
+    let (i, inputs) = preceded(multispace0, parse_params)(i)?;
+    let (i, modifiers) = parse_modifiers(i)?;
+    let (i, outputs) = opt(preceded(
+        tuple((multispace1, tag("returns"), multispace0)),
+        parse_params,
+    ))(i)?;
+
+    let state_mutability = parse_state_mutability(&modifiers, StateMutability::NonPayable)?;
+
+    Ok((
+        i,
+        Fragment::Function(Function {
+            name: name.to_string(),
+            inputs: not_indexed(inputs)?,
+            outputs: not_indexed(outputs.unwrap_or_default())?,
+            state_mutability,
+            extra_fields: ExtraFields::new(),
+        }),
+    ))
+}
+
+fn parse_event(input: &str) -> TypeParseResult<&str, Fragment> {
+    let (i, _) = tag("event")(input)?;
+    let (i, name) = preceded(multispace1, parse_identifier)(i)?;
+    let (i, inputs) = preceded(multispace0, parse_params)(i)?;
+    let (i, anonymous) = opt(preceded(multispace1, tag("anonymous")))(i)?;
+
+    let inputs = inputs
+        .into_iter()
+        .map(|param| Param {
+            indexed: Some(param.indexed.unwrap_or(false)),
+            ..param
+        })
+        .collect();
+
+    Ok((
+        i,
+        Fragment::Event(Event {
+            name: name.to_string(),
+            inputs,
+            anonymous: anonymous.is_some(),
+            extra_fields: ExtraFields::new(),
+        }),
+    ))
+}
+
+fn parse_error(input: &str) -> TypeParseResult<&str, Fragment> {
+    let (i, _) = tag("error")(input)?;
+    let (i, name) = preceded(multispace1, parse_identifier)(i)?;
+    let (i, inputs) = preceded(multispace0, parse_params)(i)?;
+
+    Ok((
+        i,
+        Fragment::Error(AbiError {
+            name: name.to_string(),
+            inputs: not_indexed(inputs)?,
+            extra_fields: ExtraFields::new(),
+        }),
+    ))
+}
+
+fn parse_constructor(input: &str) -> TypeParseResult<&str, Fragment> {
+    let (i, _) = tag("constructor")(input)?;
+    let (i, inputs) = preceded(multispace0, parse_params)(i)?;
+    let (i, modifiers) = parse_modifiers(i)?;
+
+    let state_mutability = parse_state_mutability(&modifiers, StateMutability::NonPayable)?;
+
+    Ok((
+        i,
+        Fragment::Constructor(Constructor {
+            inputs: not_indexed(inputs)?,
+            state_mutability,
+            extra_fields: ExtraFields::new(),
+        }),
+    ))
+}
+
+fn parse_receive(input: &str) -> TypeParseResult<&str, Fragment> {
+    let (i, _) = tuple((
+        tag("receive"),
+        multispace0,
+        char('('),
+        multispace0,
+        char(')'),
+    ))(input)?;
+    let (i, modifiers) = parse_modifiers(i)?;
+
+    Ok((
+        i,
+        Fragment::Receive(Receive {
+            // receive functions are payable by definition
+            state_mutability: parse_state_mutability(&modifiers, StateMutability::Payable)?,
+            extra_fields: ExtraFields::new(),
+        }),
+    ))
+}
+
+fn parse_fallback(input: &str) -> TypeParseResult<&str, Fragment> {
+    let (i, _) = tuple((
+        tag("fallback"),
+        multispace0,
+        char('('),
+        multispace0,
+        char(')'),
+    ))(input)?;
+    let (i, modifiers) = parse_modifiers(i)?;
+
+    Ok((
+        i,
+        Fragment::Fallback(Fallback {
+            state_mutability: parse_state_mutability(&modifiers, StateMutability::NonPayable)?,
+            extra_fields: ExtraFields::new(),
+        }),
+    ))
+}
+
+// Parses a parenthesized, comma separated list of params, e.g. `(address to, uint256 amount)`.
+fn parse_params(input: &str) -> TypeParseResult<&str, Vec<Param>> {
+    delimited(
+        pair(char('('), multispace0),
+        separated_list0(delimited(multispace0, char(','), multispace0), parse_param),
+        pair(multispace0, char(')')),
+    )(input)
+}
+
+// Parses a single param, i.e. a type followed by an optional data location,
+// an optional `indexed` marker and an optional name.
+fn parse_param(input: &str) -> TypeParseResult<&str, Param> {
+    let (i, type_) = parse_param_type(input)?;
+    let (i, words) = many0(preceded(multispace1, parse_identifier))(i)?;
+
+    let mut name = None;
+    let mut indexed = None;
+
+    for word in words {
+        match word {
+            "indexed" => indexed = Some(true),
+            "memory" | "calldata" | "storage" | "payable" => {}
+            _ if name.is_none() => name = Some(word.to_string()),
+            _ => return Err(nom::Err::Failure(TypeParseError::Error)),
+        }
+    }
+
+    Ok((
+        i,
+        Param {
+            name: name.unwrap_or_default(),
+            type_,
+            indexed,
+            internal_type: None,
+            component_internal_types: None,
+        },
+    ))
+}
+
+fn parse_param_type(input: &str) -> TypeParseResult<&str, Type> {
+    alt((parse_named_tuple, parse_inline_type))(input)
+}
+
+// Parses a tuple type whose components may be named, e.g. `(uint256 a, string b)[]`.
+fn parse_named_tuple(input: &str) -> TypeParseResult<&str, Type> {
+    // empty tuples are not allowed
+    let (i, params) = preceded(
+        opt(tag("tuple")),
+        verify(parse_params, |params: &[Param]| !params.is_empty()),
+    )(input)?;
+    let (i, sizes) = opt(parse_array_sizes)(i)?;
+
+    let ty = Type::Tuple(
+        params
+            .into_iter()
+            .map(|param| (param.name, param.type_))
+            .collect(),
+    );
+
+    Ok((i, array_type(ty, sizes.unwrap_or_default())))
+}
+
+fn parse_modifiers(input: &str) -> TypeParseResult<&str, Vec<&str>> {
+    many0(preceded(
+        multispace1,
+        verify(parse_identifier, |word: &str| word != "returns"),
+    ))(input)
+}
+
+fn parse_identifier(input: &str) -> TypeParseResult<&str, &str> {
+    recognize(pair(
+        take_while1(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$'),
+        take_while(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$'),
+    ))(input)
+}
+
+// Returns the state mutability given by the modifiers, or `default` if none is.
+fn parse_state_mutability<'a>(
+    modifiers: &[&str],
+    default: StateMutability,
+) -> Result<StateMutability, nom::Err<TypeParseError<&'a str>>> {
+    let mut state_mutability = default;
+
+    for modifier in modifiers {
+        match *modifier {
+            "pure" => state_mutability = StateMutability::Pure,
+            "view" | "constant" => state_mutability = StateMutability::View,
+            "payable" => state_mutability = StateMutability::Payable,
+            "nonpayable" => state_mutability = StateMutability::NonPayable,
+            "external" | "public" | "internal" | "private" | "virtual" | "override" => {}
+            _ => return Err(nom::Err::Failure(TypeParseError::Error)),
+        }
+    }
+
+    Ok(state_mutability)
+}
+
+// Only event params may be marked as indexed.
+fn not_indexed<'a>(params: Vec<Param>) -> Result<Vec<Param>, nom::Err<TypeParseError<&'a str>>> {
+    if params.iter().any(|param| param.indexed.is_some()) {
+        return Err(nom::Err::Failure(TypeParseError::Error));
+    }
+
+    Ok(params)
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn param(name: &str, type_: Type, indexed: Option<bool>) -> Param {
+        Param {
+            name: name.to_string(),
+            type_,
+            indexed,
+            internal_type: None,
+            component_internal_types: None,
+        }
+    }
+
+    #[test]
+    fn parse_function_fragment() {
+        let fragment =
+            parse_fragment("function transfer(address to, uint256 amount) external returns (bool)")
+                .unwrap();
+
+        assert_eq!(
+            fragment,
+            Fragment::Function(Function {
+                name: "transfer".to_string(),
+                inputs: vec![
+                    param("to", Type::Address, None),
+                    param("amount", Type::Uint(256), None),
+                ],
+                outputs: vec![param("", Type::Bool, None)],
+                state_mutability: StateMutability::NonPayable,
+                extra_fields: ExtraFields::new(),
+            })
+        );
+
+        let fragment = parse_fragment(
+            "function f(tuple(uint a, bytes[] b)[2] calldata xs, string memory) public view",
+        )
+        .unwrap();
+
+        assert_eq!(
+            fragment,
+            Fragment::Function(Function {
+                name: "f".to_string(),
+                inputs: vec![
+                    param(
+                        "xs",
+                        Type::FixedArray(
+                            Box::new(Type::Tuple(vec![
+                                ("a".to_string(), Type::Uint(256)),
+                                ("b".to_string(), Type::Array(Box::new(Type::Bytes))),
+                            ])),
+                            2
+                        ),
+                        None
+                    ),
+                    param("", Type::String, None),
+                ],
+                outputs: vec![],
+                state_mutability: StateMutability::View,
+                extra_fields: ExtraFields::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_event_fragment() {
+        let fragment = parse_fragment(
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+        )
+        .unwrap();
+
+        assert_eq!(
+            fragment,
+            Fragment::Event(Event {
+                name: "Transfer".to_string(),
+                inputs: vec![
+                    param("from", Type::Address, Some(true)),
+                    param("to", Type::Address, Some(true)),
+                    param("value", Type::Uint(256), Some(false)),
+                ],
+                anonymous: false,
+                extra_fields: ExtraFields::new(),
+            })
+        );
+
+        let fragment = parse_fragment("event E((uint8,bool) x) anonymous").unwrap();
+
+        assert_eq!(
+            fragment,
+            Fragment::Event(Event {
+                name: "E".to_string(),
+                inputs: vec![param(
+                    "x",
+                    Type::Tuple(vec![
+                        ("".to_string(), Type::Uint(8)),
+                        ("".to_string(), Type::Bool),
+                    ]),
+                    Some(false)
+                )],
+                anonymous: true,
+                extra_fields: ExtraFields::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_other_fragments() {
+        assert_eq!(
+            parse_fragment("constructor(address owner) payable").unwrap(),
+            Fragment::Constructor(Constructor {
+                inputs: vec![param("owner", Type::Address, None)],
+                state_mutability: StateMutability::Payable,
+                extra_fields: ExtraFields::new(),
+            })
+        );
+
+        assert_eq!(
+            parse_fragment("error Unauthorized(address caller)").unwrap(),
+            Fragment::Error(AbiError {
+                name: "Unauthorized".to_string(),
+                inputs: vec![param("caller", Type::Address, None)],
+                extra_fields: ExtraFields::new(),
+            })
+        );
+
+        assert_eq!(
+            parse_fragment("function register(function callback)").unwrap(),
+            Fragment::Function(Function {
+                name: "register".to_string(),
+                inputs: vec![param("callback", Type::Function, None)],
+                outputs: vec![],
+                state_mutability: StateMutability::NonPayable,
+                extra_fields: ExtraFields::new(),
+            })
+        );
+
+        assert_eq!(
+            parse_fragment("receive() external payable").unwrap(),
+            Fragment::Receive(Receive {
+                state_mutability: StateMutability::Payable,
+                extra_fields: ExtraFields::new(),
+            })
+        );
+        assert_eq!(
+            parse_fragment("receive() external").unwrap(),
+            Fragment::Receive(Receive {
+                state_mutability: StateMutability::Payable,
+                extra_fields: ExtraFields::new(),
+            })
+        );
+        assert_eq!(
+            parse_fragment("fallback() external").unwrap(),
+            Fragment::Fallback(Fallback {
+                state_mutability: StateMutability::NonPayable,
+                extra_fields: ExtraFields::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_invalid_fragments() {
+        let fragments = [
+            "function f(uint256 a b)",
+            "function f(uint256 indexed a)",
+            "function f() sometimes",
+            "function f(uint7)",
+            "function (uint256)",
+            "event E(uint256",
+            "struct S { uint256 a; }",
+        ];
+
+        for fragment in &fragments {
+            assert!(
+                parse_fragment(fragment).is_err(),
+                "{} should not parse",
+                fragment
+            );
+        }
+    }
+}
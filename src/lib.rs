@@ -1,13 +1,31 @@
 //! Ethereum Smart Contracts ABI (abstract binary interface) utility library.
 
 mod abi;
+pub mod eip712;
+mod error;
 mod event;
+mod human_readable;
 mod params;
+mod revert;
 mod types;
 mod values;
 
 pub use abi::*;
+pub use error::*;
 pub use event::*;
 pub use params::*;
+pub use revert::*;
 pub use types::*;
 pub use values::*;
+
+// Computes the keccak256 hash of the given bytes.
+pub(crate) fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    use tiny_keccak::{Hasher, Keccak};
+
+    let mut keccak_out = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    hasher.finalize(&mut keccak_out);
+
+    keccak_out
+}
@@ -1,7 +1,7 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, rc::Rc};
 
-use crate::{types::Type, Value};
+use crate::{types::Type, DecodeOptions, Error, Result, Value};
 
 /// ABI decoded param value.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -28,9 +28,29 @@ impl DecodedParams {
     /// Creates a reader.
     ///
     /// Parameters are indexed by name at reader creation.
-    pub fn reader(&self) -> DecodedParamsReader {
+    pub fn reader(&self) -> DecodedParamsReader<'_> {
         DecodedParamsReader::new(self)
     }
+
+    // Decodes values for the given params from bytes.
+    pub(crate) fn decode_from_slice(
+        params: &[Param],
+        bs: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<Self> {
+        let tys = params
+            .iter()
+            .map(|param| param.type_.clone())
+            .collect::<Vec<_>>();
+
+        Ok(Self::from(
+            params
+                .iter()
+                .cloned()
+                .zip(Value::decode_from_slice_with(bs, &tys, options)?)
+                .collect::<Vec<_>>(),
+        ))
+    }
 }
 
 impl std::ops::Deref for DecodedParams {
@@ -78,6 +98,185 @@ pub struct Param {
     pub type_: Type,
     /// Whether it is an indexed parameter (events only).
     pub indexed: Option<bool>,
+    /// Solidity type the parameter was declared with (JSON ABI `internalType`).
+    pub internal_type: Option<InternalType>,
+    /// Internal types of the tuple components, for tuple types and arrays of
+    /// tuples.
+    pub component_internal_types: Option<ComponentInternalTypes>,
+}
+
+impl std::fmt::Display for Param {
+    /// Formats the param in human-readable form, e.g. `address indexed from`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", human_readable_type(&self.type_))?;
+
+        if self.indexed == Some(true) {
+            write!(f, " indexed")?;
+        }
+
+        if !self.name.is_empty() {
+            write!(f, " {}", self.name)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Formats a type keeping tuple component names, e.g. `tuple(uint256 a, string b)[]`.
+fn human_readable_type(ty: &Type) -> String {
+    match ty {
+        Type::Tuple(tys) => format!(
+            "tuple({})",
+            tys.iter()
+                .map(|(name, ty)| if name.is_empty() {
+                    human_readable_type(ty)
+                } else {
+                    format!("{} {}", human_readable_type(ty), name)
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Type::Array(ty) => format!("{}[]", human_readable_type(ty)),
+        Type::FixedArray(ty, size) => format!("{}[{}]", human_readable_type(ty), size),
+        _ => ty.to_string(),
+    }
+}
+
+/// Solidity type a param was declared with, as given by the JSON ABI
+/// `internalType` field, e.g. `struct Pool.Key` or `contract IERC20`.
+///
+/// Array suffixes are not kept, they are the same as the param type's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InternalType {
+    /// Struct, optionally defined inside a contract.
+    Struct {
+        /// Contract the struct is defined in.
+        contract: Option<String>,
+        /// Struct name.
+        name: String,
+    },
+    /// Enum, optionally defined inside a contract.
+    Enum {
+        /// Contract the enum is defined in.
+        contract: Option<String>,
+        /// Enum name.
+        name: String,
+    },
+    /// Contract or interface, by name.
+    Contract(String),
+    /// `address payable`.
+    AddressPayable,
+    /// Any other type, e.g. `uint256` or a user-defined value type.
+    Other(String),
+}
+
+impl std::str::FromStr for InternalType {
+    type Err = std::convert::Infallible;
+
+    /// Parses an `internalType` string. Unrecognised types are kept as
+    /// `InternalType::Other`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = strip_array_suffix(s);
+
+        let scoped = |name: &str| match name.rsplit_once('.') {
+            Some((contract, name)) => (Some(contract.to_string()), name.to_string()),
+            None => (None, name.to_string()),
+        };
+
+        Ok(if let Some(name) = s.strip_prefix("struct ") {
+            let (contract, name) = scoped(name);
+            Self::Struct { contract, name }
+        } else if let Some(name) = s.strip_prefix("enum ") {
+            let (contract, name) = scoped(name);
+            Self::Enum { contract, name }
+        } else if let Some(name) = s.strip_prefix("contract ") {
+            Self::Contract(name.to_string())
+        } else if s == "address payable" {
+            Self::AddressPayable
+        } else {
+            Self::Other(s.to_string())
+        })
+    }
+}
+
+impl std::fmt::Display for InternalType {
+    /// Formats the internal type as in the JSON ABI, without array suffixes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scoped = |contract: &Option<String>, name: &str| match contract {
+            Some(contract) => format!("{}.{}", contract, name),
+            None => name.to_string(),
+        };
+
+        match self {
+            Self::Struct { contract, name } => write!(f, "struct {}", scoped(contract, name)),
+            Self::Enum { contract, name } => write!(f, "enum {}", scoped(contract, name)),
+            Self::Contract(name) => write!(f, "contract {}", name),
+            Self::AddressPayable => write!(f, "address payable"),
+            Self::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+// Strips trailing array suffixes, e.g. `struct S[2][]` becomes `struct S`.
+fn strip_array_suffix(mut s: &str) -> &str {
+    loop {
+        match s.strip_suffix(']').and_then(|s| s.rsplit_once('[')) {
+            Some((base, size)) if size.chars().all(|c| c.is_ascii_digit()) => s = base,
+            _ => return s,
+        }
+    }
+}
+
+/// Internal types of a tuple's components, in order, mirroring the tuple
+/// type. Each component may have its own component internal types, if it is a
+/// tuple or an array of tuples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentInternalTypes(Vec<(Option<InternalType>, Option<ComponentInternalTypes>)>);
+
+impl ComponentInternalTypes {
+    /// Creates the component internal types of the given tuple type, or array
+    /// of tuples, failing if their shape does not match the type's.
+    pub fn new(
+        ty: &Type,
+        components: Vec<(Option<InternalType>, Option<ComponentInternalTypes>)>,
+    ) -> Result<Self> {
+        let components = Self(components);
+
+        if !components.fits(ty) {
+            return Err(Error::InvalidValue(format!(
+                "component internal types do not match type {}",
+                ty
+            )));
+        }
+
+        Ok(components)
+    }
+
+    /// Returns the internal type of the component at the given index.
+    pub fn internal_type(&self, index: usize) -> Option<&InternalType> {
+        self.0.get(index)?.0.as_ref()
+    }
+
+    /// Returns the component internal types of the component at the given index.
+    pub fn components(&self, index: usize) -> Option<&ComponentInternalTypes> {
+        self.0.get(index)?.1.as_ref()
+    }
+
+    // Whether the shape matches the tuple components of the given type.
+    fn fits(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Array(ty) | Type::FixedArray(ty, _) => self.fits(ty),
+            Type::Tuple(tys) => {
+                tys.len() == self.0.len()
+                    && tys.iter().zip(&self.0).all(|((_, ty), (_, components))| {
+                        components
+                            .as_ref()
+                            .is_none_or(|components| components.fits(ty))
+                    })
+            }
+            _ => false,
+        }
+    }
 }
 
 impl<'a> Deserialize<'a> for Param {
@@ -87,26 +286,155 @@ impl<'a> Deserialize<'a> for Param {
     {
         let entry: ParamEntry = Deserialize::deserialize(deserializer)?;
 
-        let (_, ty) = parse_exact_type(Rc::new(entry.components), &entry.type_)
-            .map_err(|e| serde::de::Error::custom(e.to_string()))?;
+        Param::from_entry(entry).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Param {
+    fn from_entry(entry: ParamEntry) -> Result<Self, String> {
+        let internal_type = entry
+            .internal_type
+            .as_deref()
+            .and_then(|internal_type| internal_type.parse().ok());
+
+        let components = entry
+            .components
+            .iter()
+            .flatten()
+            .cloned()
+            .map(|entry| {
+                Self::from_entry(entry)
+                    .map(|param| (param.internal_type, param.component_internal_types))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (_, ty) =
+            parse_exact_type(Rc::new(entry.components), &entry.type_).map_err(|e| e.to_string())?;
+
+        let component_internal_types = if components
+            .iter()
+            .any(|(internal_type, components)| internal_type.is_some() || components.is_some())
+        {
+            Some(ComponentInternalTypes::new(&ty, components).map_err(|e| e.to_string())?)
+        } else {
+            None
+        };
 
         Ok(Param {
-            name: entry.name.to_string(),
+            name: entry.name,
             type_: ty,
             indexed: entry.indexed,
+            internal_type,
+            component_internal_types,
         })
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl Serialize for Param {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        ParamEntry::new(
+            &self.name,
+            &self.type_,
+            self.indexed,
+            self.internal_type.as_ref(),
+            self.component_internal_types.as_ref(),
+        )
+        .serialize(serializer)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 struct ParamEntry {
     pub name: String,
     #[serde(rename = "type")]
     pub type_: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub indexed: Option<bool>,
+    #[serde(rename = "internalType", skip_serializing_if = "Option::is_none")]
+    pub internal_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Vec<ParamEntry>>,
 }
 
+impl ParamEntry {
+    fn new(
+        name: &str,
+        ty: &Type,
+        indexed: Option<bool>,
+        internal_type: Option<&InternalType>,
+        components: Option<&ComponentInternalTypes>,
+    ) -> Self {
+        // component internal types left over from a previous type are ignored
+        let components = components.filter(|components| components.fits(ty));
+        let (type_, components) = Self::type_entry(ty, components);
+
+        Self {
+            name: name.to_string(),
+            type_,
+            indexed,
+            internal_type: internal_type
+                .map(|internal_type| format!("{}{}", internal_type, array_suffix(ty))),
+            components,
+        }
+    }
+
+    // Returns the JSON ABI type string and components of the given type,
+    // e.g. (uint256,string)[] becomes "tuple[]" with two components. Component
+    // internal types are taken from `components`, if any.
+    fn type_entry(
+        ty: &Type,
+        components: Option<&ComponentInternalTypes>,
+    ) -> (String, Option<Vec<ParamEntry>>) {
+        match ty {
+            Type::Tuple(tys) => (
+                "tuple".to_string(),
+                Some(
+                    tys.iter()
+                        .enumerate()
+                        .map(|(i, (name, ty))| {
+                            let component = components.and_then(|components| components.0.get(i));
+
+                            Self::new(
+                                name,
+                                ty,
+                                None,
+                                component.and_then(|(internal_type, _)| internal_type.as_ref()),
+                                component.and_then(|(_, components)| components.as_ref()),
+                            )
+                        })
+                        .collect(),
+                ),
+            ),
+
+            Type::Array(ty) => {
+                let (type_, components) = Self::type_entry(ty, components);
+
+                (format!("{}[]", type_), components)
+            }
+
+            Type::FixedArray(ty, size) => {
+                let (type_, components) = Self::type_entry(ty, components);
+
+                (format!("{}[{}]", type_, size), components)
+            }
+
+            _ => (ty.to_string(), None),
+        }
+    }
+}
+
+// Returns the array suffixes of a type, e.g. `[2][]` for `uint256[2][]`.
+fn array_suffix(ty: &Type) -> String {
+    match ty {
+        Type::Array(ty) => format!("{}[]", array_suffix(ty)),
+        Type::FixedArray(ty, size) => format!("{}[{}]", array_suffix(ty), size),
+        _ => String::new(),
+    }
+}
+
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -114,13 +442,13 @@ use nom::{
     combinator::opt,
     combinator::{map_res, recognize, verify},
     exact,
-    multi::many1,
-    sequence::delimited,
+    multi::{many1, separated_list1},
+    sequence::{delimited, pair, preceded},
     IResult,
 };
 
 #[derive(Debug)]
-enum TypeParseError<I> {
+pub(crate) enum TypeParseError<I> {
     Error,
     NomError(nom::error::Error<I>),
 }
@@ -141,12 +469,23 @@ impl<I> nom::error::ParseError<I> for TypeParseError<I> {
     }
 }
 
-type TypeParseResult<I, O> = IResult<I, O, TypeParseError<I>>;
+pub(crate) type TypeParseResult<I, O> = IResult<I, O, TypeParseError<I>>;
 
 fn map_error<I, O>(res: IResult<I, O>) -> TypeParseResult<I, O> {
     res.map_err(|err| err.map(From::from))
 }
 
+impl std::str::FromStr for Type {
+    type Err = Error;
+
+    /// Parses a type string, e.g. `uint256[2][]` or `(address,(uint8,bytes)[])`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_exact_type(Rc::new(None), s)
+            .map(|(_, ty)| ty)
+            .map_err(|_| Error::TypeParse(s.to_string()))
+    }
+}
+
 fn parse_exact_type(
     components: Rc<Option<Vec<ParamEntry>>>,
     input: &str,
@@ -154,6 +493,11 @@ fn parse_exact_type(
     exact!(input, parse_type(components.clone()))
 }
 
+// Parses a type string without tuple components (only inline tuples are supported).
+pub(crate) fn parse_inline_type(input: &str) -> TypeParseResult<&str, Type> {
+    parse_type(Rc::new(None))(input)
+}
+
 fn parse_type(
     components: Rc<Option<Vec<ParamEntry>>>,
 ) -> impl Fn(&str) -> TypeParseResult<&str, Type> {
@@ -170,11 +514,15 @@ fn parse_simple_type(
 ) -> impl Fn(&str) -> TypeParseResult<&str, Type> {
     move |input: &str| {
         alt((
+            parse_inline_tuple,
             parse_tuple(components.clone()),
             parse_uint,
             parse_int,
+            parse_ufixed,
+            parse_fixed,
             parse_address,
             parse_bool,
+            parse_function,
             parse_string,
             parse_bytes,
         ))(input)
@@ -182,15 +530,44 @@ fn parse_simple_type(
 }
 
 fn parse_uint(input: &str) -> TypeParseResult<&str, Type> {
-    map_error(
-        verify(parse_sized("uint"), check_int_size)(input).map(|(i, size)| (i, Type::Uint(size))),
-    )
+    let (i, _) = map_error(tag("uint")(input))?;
+    let (i, size) = map_error(opt(verify(parse_integer, check_int_size))(i))?;
+
+    // `uint` is an alias for `uint256`
+    Ok((i, Type::Uint(size.unwrap_or(256))))
 }
 
 fn parse_int(input: &str) -> TypeParseResult<&str, Type> {
-    map_error(
-        verify(parse_sized("int"), check_int_size)(input).map(|(i, size)| (i, Type::Int(size))),
-    )
+    let (i, _) = map_error(tag("int")(input))?;
+    let (i, size) = map_error(opt(verify(parse_integer, check_int_size))(i))?;
+
+    // `int` is an alias for `int256`
+    Ok((i, Type::Int(size.unwrap_or(256))))
+}
+
+fn parse_ufixed(input: &str) -> TypeParseResult<&str, Type> {
+    let (i, _) = map_error(tag("ufixed")(input))?;
+    let (i, (size, decimals)) = parse_fixed_sizes(i)?;
+
+    Ok((i, Type::UFixed(size, decimals)))
+}
+
+fn parse_fixed(input: &str) -> TypeParseResult<&str, Type> {
+    let (i, _) = map_error(tag("fixed")(input))?;
+    let (i, (size, decimals)) = parse_fixed_sizes(i)?;
+
+    Ok((i, Type::Fixed(size, decimals)))
+}
+
+// Parses the `<M>x<N>` suffix of fixed point types.
+fn parse_fixed_sizes(input: &str) -> TypeParseResult<&str, (usize, usize)> {
+    let (i, sizes) = map_error(opt(pair(
+        verify(parse_integer, check_int_size),
+        preceded(char('x'), verify(parse_integer, check_fixed_decimals)),
+    ))(input))?;
+
+    // `fixed` and `ufixed` are aliases for `fixed128x18` and `ufixed128x18`
+    Ok((i, sizes.unwrap_or((128, 18))))
 }
 
 fn parse_address(input: &str) -> TypeParseResult<&str, Type> {
@@ -201,6 +578,10 @@ fn parse_bool(input: &str) -> TypeParseResult<&str, Type> {
     map_error(tag("bool")(input).map(|(i, _)| (i, Type::Bool)))
 }
 
+fn parse_function(input: &str) -> TypeParseResult<&str, Type> {
+    map_error(tag("function")(input).map(|(i, _)| (i, Type::Function)))
+}
+
 fn parse_string(input: &str) -> TypeParseResult<&str, Type> {
     map_error(tag("string")(input).map(|(i, _)| (i, Type::String)))
 }
@@ -220,20 +601,28 @@ fn parse_array(
     move |input: &str| {
         let (i, ty) = parse_simple_type(components.clone())(input)?;
 
-        let (i, sizes) = map_error(many1(delimited(char('['), opt(parse_integer), char(']')))(
-            i,
-        ))?;
+        let (i, sizes) = parse_array_sizes(i)?;
 
-        let array_from_size = |ty: Type, size: Option<usize>| match size {
-            None => Type::Array(Box::new(ty)),
-            Some(size) => Type::FixedArray(Box::new(ty), size),
-        };
+        Ok((i, array_type(ty, sizes)))
+    }
+}
 
-        let init_arr_ty = array_from_size(ty, sizes[0]);
-        let arr_ty = sizes.into_iter().skip(1).fold(init_arr_ty, array_from_size);
+// Parses one or more array size suffixes, e.g. `[2][]`.
+pub(crate) fn parse_array_sizes(input: &str) -> TypeParseResult<&str, Vec<Option<usize>>> {
+    // zero length fixed arrays are not allowed
+    map_error(many1(delimited(
+        char('['),
+        opt(verify(parse_integer, |size| *size > 0)),
+        char(']'),
+    ))(input))
+}
 
-        Ok((i, arr_ty))
-    }
+// Wraps the given type into (possibly nested) array types, innermost size first.
+pub(crate) fn array_type(ty: Type, sizes: Vec<Option<usize>>) -> Type {
+    sizes.into_iter().fold(ty, |ty, size| match size {
+        None => Type::Array(Box::new(ty)),
+        Some(size) => Type::FixedArray(Box::new(ty), size),
+    })
 }
 
 fn parse_tuple(
@@ -243,38 +632,42 @@ fn parse_tuple(
         let (i, _) = map_error(tag("tuple")(input))?;
 
         let tys = match components.clone().as_ref() {
-            Some(cs) => cs
-                .clone()
-                .into_iter()
-                .try_fold(vec![], |mut param_tys, param| {
-                    let comps = match param.components.as_ref() {
-                        Some(comps) => Some(comps.clone()),
-                        None => None,
-                    };
-
-                    let ty = match parse_exact_type(Rc::new(comps), &param.type_) {
-                        Ok((_, ty)) => ty,
-                        Err(_) => return Err(nom::Err::Failure(TypeParseError::Error)),
-                    };
-
-                    param_tys.push((param.name, ty));
-
-                    Ok(param_tys)
-                }),
+            // empty tuples are not allowed
+            Some(cs) if !cs.is_empty() => {
+                cs.clone()
+                    .into_iter()
+                    .try_fold(vec![], |mut param_tys, param| {
+                        let ty = match parse_exact_type(Rc::new(param.components), &param.type_) {
+                            Ok((_, ty)) => ty,
+                            Err(_) => return Err(nom::Err::Failure(TypeParseError::Error)),
+                        };
+
+                        param_tys.push((param.name, ty));
+
+                        Ok(param_tys)
+                    })
+            }
 
-            None => Err(nom::Err::Failure(TypeParseError::Error)),
+            _ => Err(nom::Err::Failure(TypeParseError::Error)),
         }?;
 
         Ok((i, Type::Tuple(tys)))
     }
 }
 
-fn parse_sized(t: &str) -> impl Fn(&str) -> IResult<&str, usize> + '_ {
-    move |input: &str| {
-        let (i, _) = tag(t)(input)?;
+fn parse_inline_tuple(input: &str) -> TypeParseResult<&str, Type> {
+    let (i, tys) = preceded(
+        opt(tag("tuple")),
+        delimited(
+            char('('),
+            separated_list1(char(','), parse_inline_type),
+            char(')'),
+        ),
+    )(input)?;
 
-        parse_integer(i)
-    }
+    let tys = tys.into_iter().map(|ty| (String::new(), ty)).collect();
+
+    Ok((i, Type::Tuple(tys)))
 }
 
 fn parse_integer(input: &str) -> IResult<&str, usize> {
@@ -284,7 +677,11 @@ fn parse_integer(input: &str) -> IResult<&str, usize> {
 fn check_int_size(i: &usize) -> bool {
     let i = *i;
 
-    i > 0 && i <= 256 && i % 8 == 0
+    i > 0 && i <= 256 && i.is_multiple_of(8)
+}
+
+fn check_fixed_decimals(i: &usize) -> bool {
+    *i <= 80
 }
 
 fn check_fixed_bytes_size(i: &usize) -> bool {
@@ -314,7 +711,9 @@ mod test {
                 Param {
                     name: "a".to_string(),
                     type_: Type::Uint(i),
-                    indexed: None
+                    indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
                 }
             );
         }
@@ -335,7 +734,37 @@ mod test {
                 Param {
                     name: "a".to_string(),
                     type_: Type::Int(i),
-                    indexed: None
+                    indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn deserialize_fixed() {
+        for (ty, expected) in &[
+            ("fixed128x18", Type::Fixed(128, 18)),
+            ("ufixed8x80", Type::UFixed(8, 80)),
+            ("fixed", Type::Fixed(128, 18)),
+            ("ufixed[]", Type::Array(Box::new(Type::UFixed(128, 18)))),
+        ] {
+            let v = json!({
+                "name": "a",
+                "type": ty,
+            });
+
+            let param: Param = serde_json::from_value(v).unwrap();
+
+            assert_eq!(
+                param,
+                Param {
+                    name: "a".to_string(),
+                    type_: expected.clone(),
+                    indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
                 }
             );
         }
@@ -355,7 +784,9 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Address,
-                indexed: None
+                indexed: None,
+                internal_type: None,
+                component_internal_types: None,
             }
         );
     }
@@ -374,7 +805,9 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Bool,
-                indexed: None
+                indexed: None,
+                internal_type: None,
+                component_internal_types: None,
             }
         );
     }
@@ -393,7 +826,9 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::String,
-                indexed: None
+                indexed: None,
+                internal_type: None,
+                component_internal_types: None,
             }
         );
     }
@@ -413,7 +848,9 @@ mod test {
                 Param {
                     name: "a".to_string(),
                     type_: Type::FixedBytes(i),
-                    indexed: None
+                    indexed: None,
+                    internal_type: None,
+                    component_internal_types: None,
                 }
             );
         }
@@ -430,7 +867,9 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Bytes,
-                indexed: None
+                indexed: None,
+                internal_type: None,
+                component_internal_types: None,
             }
         );
     }
@@ -449,6 +888,8 @@ mod test {
                 name: "a".to_string(),
                 type_: Type::Array(Box::new(Type::Uint(256))),
                 indexed: None,
+                internal_type: None,
+                component_internal_types: None,
             }
         );
     }
@@ -467,6 +908,8 @@ mod test {
                 name: "a".to_string(),
                 type_: Type::Array(Box::new(Type::Array(Box::new(Type::Address)))),
                 indexed: None,
+                internal_type: None,
+                component_internal_types: None,
             }
         );
     }
@@ -485,6 +928,8 @@ mod test {
                 name: "a".to_string(),
                 type_: Type::Array(Box::new(Type::FixedArray(Box::new(Type::String), 2))),
                 indexed: None,
+                internal_type: None,
+                component_internal_types: None,
             }
         );
 
@@ -500,8 +945,328 @@ mod test {
                 name: "a".to_string(),
                 type_: Type::FixedArray(Box::new(Type::Array(Box::new(Type::String))), 3),
                 indexed: None,
+                internal_type: None,
+                component_internal_types: None,
+            }
+        );
+    }
+
+    #[test]
+    fn type_from_str() {
+        use std::str::FromStr;
+
+        assert_eq!(
+            Type::from_str("uint256[2][]").unwrap(),
+            Type::Array(Box::new(Type::FixedArray(Box::new(Type::Uint(256)), 2)))
+        );
+
+        assert_eq!(
+            Type::from_str("(address,(uint8,bytes)[])").unwrap(),
+            Type::Tuple(vec![
+                ("".to_string(), Type::Address),
+                (
+                    "".to_string(),
+                    Type::Array(Box::new(Type::Tuple(vec![
+                        ("".to_string(), Type::Uint(8)),
+                        ("".to_string(), Type::Bytes)
+                    ])))
+                )
+            ])
+        );
+
+        assert_eq!(
+            Type::from_str("tuple(bool)").unwrap(),
+            Type::Tuple(vec![("".to_string(), Type::Bool)])
+        );
+        assert_eq!(Type::from_str("uint").unwrap(), Type::Uint(256));
+        assert_eq!(
+            Type::from_str("int[]").unwrap(),
+            Type::Array(Box::new(Type::Int(256)))
+        );
+
+        for s in &[
+            "uint7",
+            "bytes33",
+            "tuple",
+            "(uint256",
+            "uint256[",
+            "foo",
+            "",
+            "fixed128",
+            "fixed7x18",
+            "ufixed128x81",
+            "()",
+            "()[]",
+            "uint8[0]",
+            "uint8[0][]",
+        ] {
+            assert!(Type::from_str(s).is_err(), "{} should not parse", s);
+        }
+    }
+
+    #[test]
+    fn type_from_str_round_trip() {
+        use std::str::FromStr;
+
+        let tys = vec![
+            Type::Uint(8),
+            Type::Int(256),
+            Type::Address,
+            Type::Bool,
+            Type::String,
+            Type::Bytes,
+            Type::FixedBytes(32),
+            Type::Fixed(128, 18),
+            Type::UFixed(256, 0),
+            Type::Function,
+            Type::FixedArray(Box::new(Type::Array(Box::new(Type::String))), 3),
+            Type::Array(Box::new(Type::Tuple(vec![
+                ("".to_string(), Type::FixedBytes(4)),
+                (
+                    "".to_string(),
+                    Type::Tuple(vec![("".to_string(), Type::Int(8))]),
+                ),
+            ]))),
+        ];
+
+        for ty in tys {
+            assert_eq!(Type::from_str(&ty.to_string()).unwrap(), ty);
+        }
+    }
+
+    #[test]
+    fn display_param() {
+        let param = Param {
+            name: "xs".to_string(),
+            type_: Type::Array(Box::new(Type::Tuple(vec![
+                ("a".to_string(), Type::Uint(256)),
+                ("".to_string(), Type::FixedArray(Box::new(Type::Bytes), 2)),
+            ]))),
+            indexed: Some(true),
+            internal_type: None,
+            component_internal_types: None,
+        };
+
+        assert_eq!(param.to_string(), "tuple(uint256 a, bytes[2])[] indexed xs");
+
+        let param = Param {
+            name: "".to_string(),
+            type_: Type::Address,
+            indexed: None,
+            internal_type: None,
+            component_internal_types: None,
+        };
+
+        assert_eq!(param.to_string(), "address");
+    }
+
+    #[test]
+    fn serialize_param() {
+        let param = Param {
+            name: "a".to_string(),
+            type_: Type::FixedArray(Box::new(Type::Array(Box::new(Type::String))), 3),
+            indexed: Some(true),
+            internal_type: None,
+            component_internal_types: None,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&param).unwrap(),
+            json!({
+                "name": "a",
+                "type": "string[][3]",
+                "indexed": true,
+            })
+        );
+    }
+
+    #[test]
+    fn serialize_tuple() {
+        let v = json!({
+          "name": "s",
+          "type": "tuple[2][]",
+          "components": [
+            {
+              "name": "a",
+              "type": "uint256"
+            },
+            {
+              "name": "c",
+              "type": "tuple",
+              "components": [
+                {
+                  "name": "x",
+                  "type": "bytes32[]"
+                }
+              ]
+            }
+          ]
+        });
+
+        let param: Param = serde_json::from_value(v.clone()).unwrap();
+
+        assert_eq!(serde_json::to_value(&param).unwrap(), v);
+    }
+
+    #[test]
+    fn internal_type() {
+        let cases = vec![
+            (
+                "struct Pool.Key",
+                InternalType::Struct {
+                    contract: Some("Pool".to_string()),
+                    name: "Key".to_string(),
+                },
+            ),
+            (
+                "struct Key",
+                InternalType::Struct {
+                    contract: None,
+                    name: "Key".to_string(),
+                },
+            ),
+            (
+                "enum Side",
+                InternalType::Enum {
+                    contract: None,
+                    name: "Side".to_string(),
+                },
+            ),
+            (
+                "contract IERC20",
+                InternalType::Contract("IERC20".to_string()),
+            ),
+            ("address payable", InternalType::AddressPayable),
+            ("Price", InternalType::Other("Price".to_string())),
+        ];
+
+        for (s, internal_type) in cases {
+            assert_eq!(s.parse::<InternalType>().unwrap(), internal_type);
+            assert_eq!(internal_type.to_string(), s);
+        }
+
+        assert_eq!(
+            "enum Book.Side[2][]".parse::<InternalType>().unwrap(),
+            InternalType::Enum {
+                contract: Some("Book".to_string()),
+                name: "Side".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn internal_type_round_trip() {
+        let v = json!({
+          "name": "keys",
+          "type": "tuple[]",
+          "internalType": "struct Pool.Key[]",
+          "components": [
+            {
+              "name": "token",
+              "type": "address",
+              "internalType": "contract IERC20"
+            },
+            {
+              "name": "fee",
+              "type": "uint24",
+              "internalType": "uint24"
+            },
+            {
+              "name": "inner",
+              "type": "tuple",
+              "internalType": "struct Inner",
+              "components": [
+                {
+                  "name": "side",
+                  "type": "uint8",
+                  "internalType": "enum Side"
+                }
+              ]
+            }
+          ]
+        });
+
+        let param: Param = serde_json::from_value(v.clone()).unwrap();
+
+        let inner = Type::Tuple(vec![("side".to_string(), Type::Uint(8))]);
+        let ty = Type::Array(Box::new(Type::Tuple(vec![
+            ("token".to_string(), Type::Address),
+            ("fee".to_string(), Type::Uint(24)),
+            ("inner".to_string(), inner.clone()),
+        ])));
+
+        let side = InternalType::Enum {
+            contract: None,
+            name: "Side".to_string(),
+        };
+        let inner_internal_types =
+            ComponentInternalTypes::new(&inner, vec![(Some(side.clone()), None)]).unwrap();
+
+        assert_eq!(
+            param,
+            Param {
+                name: "keys".to_string(),
+                type_: ty.clone(),
+                indexed: None,
+                internal_type: Some(InternalType::Struct {
+                    contract: Some("Pool".to_string()),
+                    name: "Key".to_string(),
+                }),
+                component_internal_types: Some(
+                    ComponentInternalTypes::new(
+                        &ty,
+                        vec![
+                            (Some(InternalType::Contract("IERC20".to_string())), None),
+                            (Some(InternalType::Other("uint24".to_string())), None),
+                            (
+                                Some(InternalType::Struct {
+                                    contract: None,
+                                    name: "Inner".to_string(),
+                                }),
+                                Some(inner_internal_types.clone()),
+                            ),
+                        ],
+                    )
+                    .unwrap()
+                ),
             }
         );
+
+        let components = param.component_internal_types.as_ref().unwrap();
+        assert_eq!(
+            components.internal_type(0),
+            Some(&InternalType::Contract("IERC20".to_string()))
+        );
+        assert_eq!(
+            components.components(2).unwrap().internal_type(0),
+            Some(&side)
+        );
+        assert!(components.components(0).is_none());
+
+        assert_eq!(serde_json::to_value(&param).unwrap(), v);
+
+        // shapes must match the type
+        assert!(ComponentInternalTypes::new(&inner, vec![]).is_err());
+        assert!(ComponentInternalTypes::new(&Type::Uint(8), vec![]).is_err());
+        assert!(ComponentInternalTypes::new(
+            &ty,
+            vec![
+                (None, None),
+                (None, Some(inner_internal_types.clone())),
+                (None, None)
+            ]
+        )
+        .is_err());
+
+        // component internal types that no longer fit the type are not serialized
+        let param = Param {
+            type_: inner,
+            ..param
+        };
+        let v = serde_json::to_value(&param).unwrap();
+
+        assert_eq!(v["internalType"], "struct Pool.Key");
+        assert!(v["components"][0].get("internalType").is_none());
     }
 
     #[test]
@@ -553,6 +1318,8 @@ mod test {
                     )
                 ]),
                 indexed: None,
+                internal_type: None,
+                component_internal_types: None,
             }
         )
     }
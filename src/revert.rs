@@ -0,0 +1,231 @@
+use ethereum_types::U256;
+
+use crate::{abi::read_selector, Abi, AbiError, DecodedParams, Error, Result, Type, Value};
+
+/// Selector of Solidity's built-in `Error(string)` revert payload.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of Solidity's built-in `Panic(uint256)` revert payload.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decoded revert reason.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RevertReason<'a> {
+    /// Built-in `Error(string)` revert, e.g. from `require(cond, "reason")`.
+    Error(String),
+    /// Built-in `Panic(uint256)` revert, e.g. from a failing `assert` or an overflow.
+    Panic(PanicCode),
+    /// Contract defined custom error.
+    Custom(&'a AbiError, DecodedParams),
+}
+
+/// Solidity panic codes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PanicCode {
+    /// Generic compiler inserted panic (0x00).
+    Generic,
+    /// Failed `assert` (0x01).
+    Assert,
+    /// Arithmetic overflow or underflow outside of an `unchecked` block (0x11).
+    ArithmeticOverflow,
+    /// Division or modulo by zero (0x12).
+    DivisionByZero,
+    /// Conversion of a too big or negative value into an enum type (0x21).
+    InvalidEnumValue,
+    /// Access to an incorrectly encoded storage byte array (0x22).
+    InvalidStorageByteArray,
+    /// `.pop()` on an empty array (0x31).
+    EmptyArrayPop,
+    /// Array, `bytesN` or array slice index out of bounds (0x32).
+    ArrayOutOfBounds,
+    /// Too much memory allocated or array created too large (0x41).
+    OutOfMemory,
+    /// Call to a zero-initialized variable of internal function type (0x51).
+    InvalidInternalFunction,
+    /// Panic code not documented by Solidity.
+    Unknown(U256),
+}
+
+impl PanicCode {
+    /// Returns the panic code's numeric value.
+    pub fn code(&self) -> U256 {
+        match self {
+            PanicCode::Generic => U256::from(0x00),
+            PanicCode::Assert => U256::from(0x01),
+            PanicCode::ArithmeticOverflow => U256::from(0x11),
+            PanicCode::DivisionByZero => U256::from(0x12),
+            PanicCode::InvalidEnumValue => U256::from(0x21),
+            PanicCode::InvalidStorageByteArray => U256::from(0x22),
+            PanicCode::EmptyArrayPop => U256::from(0x31),
+            PanicCode::ArrayOutOfBounds => U256::from(0x32),
+            PanicCode::OutOfMemory => U256::from(0x41),
+            PanicCode::InvalidInternalFunction => U256::from(0x51),
+            PanicCode::Unknown(code) => *code,
+        }
+    }
+
+    /// Returns the documented meaning of the panic code.
+    pub fn description(&self) -> &'static str {
+        match self {
+            PanicCode::Generic => "generic compiler inserted panic",
+            PanicCode::Assert => "assertion failed",
+            PanicCode::ArithmeticOverflow => "arithmetic overflow or underflow",
+            PanicCode::DivisionByZero => "division or modulo by zero",
+            PanicCode::InvalidEnumValue => "invalid enum value",
+            PanicCode::InvalidStorageByteArray => "incorrectly encoded storage byte array",
+            PanicCode::EmptyArrayPop => "pop on empty array",
+            PanicCode::ArrayOutOfBounds => "array index out of bounds",
+            PanicCode::OutOfMemory => "out of memory",
+            PanicCode::InvalidInternalFunction => "call to zero-initialized internal function",
+            PanicCode::Unknown(_) => "unknown panic code",
+        }
+    }
+}
+
+impl From<U256> for PanicCode {
+    fn from(code: U256) -> Self {
+        if code > U256::from(u8::MAX) {
+            return PanicCode::Unknown(code);
+        }
+
+        match code.low_u32() {
+            0x00 => PanicCode::Generic,
+            0x01 => PanicCode::Assert,
+            0x11 => PanicCode::ArithmeticOverflow,
+            0x12 => PanicCode::DivisionByZero,
+            0x21 => PanicCode::InvalidEnumValue,
+            0x22 => PanicCode::InvalidStorageByteArray,
+            0x31 => PanicCode::EmptyArrayPop,
+            0x32 => PanicCode::ArrayOutOfBounds,
+            0x41 => PanicCode::OutOfMemory,
+            0x51 => PanicCode::InvalidInternalFunction,
+            _ => PanicCode::Unknown(code),
+        }
+    }
+}
+
+impl std::fmt::Display for PanicCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (0x{:x})", self.description(), self.code())
+    }
+}
+
+/// Decodes revert data returned by a failed call.
+///
+/// The built-in `Error(string)` and `Panic(uint256)` payloads are always
+/// recognised. When an ABI is given, its custom errors are tried as well.
+pub fn decode_revert<'a>(revert_data: &[u8], abi: Option<&'a Abi>) -> Result<RevertReason<'a>> {
+    let selector = read_selector(revert_data)?;
+
+    if selector == ERROR_SELECTOR {
+        let values = Value::decode_from_slice(&revert_data[4..], &[Type::String])?;
+
+        return match values.into_iter().next() {
+            Some(Value::String(reason)) => Ok(RevertReason::Error(reason)),
+            // should always be a single Value::String
+            _ => unreachable!(),
+        };
+    }
+
+    if selector == PANIC_SELECTOR {
+        let values = Value::decode_from_slice(&revert_data[4..], &[Type::Uint(256)])?;
+
+        return match values.into_iter().next() {
+            Some(Value::Uint(code, _)) => Ok(RevertReason::Panic(PanicCode::from(code))),
+            // should always be a single Value::Uint
+            _ => unreachable!(),
+        };
+    }
+
+    match abi {
+        Some(abi) => {
+            let (e, decoded_params) = abi.decode_error_from_slice(revert_data)?;
+
+            Ok(RevertReason::Custom(e, decoded_params))
+        }
+
+        None => Err(Error::UnknownSelector(selector)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{InternalType, Param};
+
+    use super::*;
+
+    #[test]
+    fn decode_error_string() {
+        let mut revert_data = ERROR_SELECTOR.to_vec();
+        revert_data.extend(Value::encode(&[Value::String(
+            "insufficient balance".to_string(),
+        )]));
+
+        assert_eq!(
+            decode_revert(&revert_data, None).expect("decode_revert failed"),
+            RevertReason::Error("insufficient balance".to_string())
+        );
+    }
+
+    #[test]
+    fn decode_panic() {
+        let cases = vec![
+            (0x01, PanicCode::Assert),
+            (0x11, PanicCode::ArithmeticOverflow),
+            (0x12, PanicCode::DivisionByZero),
+            (0x32, PanicCode::ArrayOutOfBounds),
+            (0x99, PanicCode::Unknown(U256::from(0x99))),
+        ];
+
+        for (code, expected) in cases {
+            let mut revert_data = PANIC_SELECTOR.to_vec();
+            revert_data.extend(Value::encode(&[Value::Uint(U256::from(code), 256)]));
+
+            assert_eq!(
+                decode_revert(&revert_data, None).expect("decode_revert failed"),
+                RevertReason::Panic(expected)
+            );
+            assert_eq!(expected.code(), U256::from(code));
+        }
+
+        assert_eq!(
+            PanicCode::ArithmeticOverflow.to_string(),
+            "arithmetic overflow or underflow (0x11)"
+        );
+    }
+
+    #[test]
+    fn decode_custom_error() {
+        let s = r#"[{"inputs":[{"internalType":"address","name":"owner","type":"address"}],"name":"Unauthorized","type":"error"}]"#;
+        let abi = Abi::from_str(s).unwrap();
+
+        let value = Value::Address(ethereum_types::H160::random());
+
+        let mut revert_data = abi.errors[0].selector().to_vec();
+        revert_data.extend(Value::encode(std::slice::from_ref(&value)));
+
+        assert_eq!(
+            decode_revert(&revert_data, Some(&abi)).expect("decode_revert failed"),
+            RevertReason::Custom(
+                &abi.errors[0],
+                DecodedParams::from(vec![(
+                    Param {
+                        name: "owner".to_string(),
+                        type_: Type::Address,
+                        indexed: None,
+                        internal_type: Some(InternalType::Other("address".to_string())),
+                        component_internal_types: None,
+                    },
+                    value
+                )])
+            )
+        );
+
+        assert!(decode_revert(&revert_data, None).is_err());
+        assert!(decode_revert(&[], Some(&abi)).is_err());
+    }
+}
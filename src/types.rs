@@ -5,10 +5,16 @@ pub enum Type {
     Uint(usize),
     /// Signed int type (int<M>).
     Int(usize),
+    /// Unsigned fixed point decimal type (ufixed<M>x<N>).
+    UFixed(usize, usize),
+    /// Signed fixed point decimal type (fixed<M>x<N>).
+    Fixed(usize, usize),
     /// Address type (address).
     Address,
     /// Bool type (bool).
     Bool,
+    /// Function type (function), i.e. an address followed by a function selector.
+    Function,
     /// Fixed size bytes type (bytes<M>).
     FixedBytes(usize),
     /// Fixed size array type (T\[k\])
@@ -29,8 +35,11 @@ impl Type {
         match self {
             Type::Uint(_) => false,
             Type::Int(_) => false,
+            Type::UFixed(_, _) => false,
+            Type::Fixed(_, _) => false,
             Type::Address => false,
             Type::Bool => false,
+            Type::Function => false,
             Type::FixedBytes(_) => false,
             Type::FixedArray(ty, _) => ty.is_dynamic(),
             Type::String => true,
@@ -46,8 +55,11 @@ impl std::fmt::Display for Type {
         match self {
             Type::Uint(size) => write!(f, "uint{}", size),
             Type::Int(size) => write!(f, "int{}", size),
+            Type::UFixed(size, decimals) => write!(f, "ufixed{}x{}", size, decimals),
+            Type::Fixed(size, decimals) => write!(f, "fixed{}x{}", size, decimals),
             Type::Address => write!(f, "address"),
             Type::Bool => write!(f, "bool"),
+            Type::Function => write!(f, "function"),
             Type::String => write!(f, "string"),
             Type::FixedBytes(size) => write!(f, "bytes{}", size),
             Type::Bytes => write!(f, "bytes"),
@@ -64,3 +76,12 @@ impl std::fmt::Display for Type {
         }
     }
 }
+
+impl serde::Serialize for Type {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
@@ -1,7 +1,6 @@
-use anyhow::{anyhow, Result};
 use ethereum_types::{H160, U256};
 
-use crate::types::Type;
+use crate::{types::Type, Error, Result};
 
 /// ABI decoded value.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -9,11 +8,31 @@ pub enum Value {
     /// Unsigned int value (uint<M>).
     Uint(U256, usize),
     /// Signed int value (int<M>).
+    ///
+    /// The value is stored as a 256 bits two's complement integer, e.g. `-1` is
+    /// stored as `U256::MAX`. See [`Value::from_i64`] and [`Value::to_i128`].
     Int(U256, usize),
+    /// Unsigned fixed point decimal value (ufixed<M>x<N>).
+    ///
+    /// The value is stored as an unsigned integer scaled by 10^N, e.g. `1.5` as
+    /// a `ufixed128x18` is `UFixed(1500000000000000000, 128, 18)`.
+    UFixed(U256, usize, usize),
+    /// Signed fixed point decimal value (fixed<M>x<N>).
+    ///
+    /// The value is stored as a 256 bits two's complement integer scaled by
+    /// 10^N, like [`Value::Int`] and [`Value::UFixed`].
+    Fixed(U256, usize, usize),
     /// Address value (address).
     Address(H160),
     /// Bool value (bool).
     Bool(bool),
+    /// Function value (function).
+    Function {
+        /// Contract address.
+        address: H160,
+        /// Function selector.
+        selector: [u8; 4],
+    },
     /// Fixed size bytes value (bytes<M>).
     FixedBytes(Vec<u8>),
     /// Fixed size array value (T\[k\]).
@@ -30,12 +49,282 @@ pub enum Value {
     Tuple(Vec<(String, Value)>),
 }
 
+/// Limits applied when decoding values, e.g. from untrusted input.
+///
+/// By default, the number of decoded values and the size of decoded data are
+/// bounded by the input length, which canonical encodings never exceed. This
+/// stops inputs whose offsets point at the same data many times from decoding
+/// into much more data than they hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Maximum total number of decoded values, counting every array element
+    /// and tuple member except static tuples and fixed arrays, which are
+    /// encoded in place and bounded by their members.
+    /// Defaults to the input length in bytes.
+    pub max_elements: Option<usize>,
+    /// Maximum nesting depth of arrays and tuples.
+    pub max_depth: usize,
+    /// Maximum total size of decoded data in bytes. Bytes, strings and
+    /// `bytes<M>` values count their length, every other non-composite value
+    /// counts 32 bytes.
+    /// Defaults to the input length in bytes.
+    pub max_output_bytes: Option<usize>,
+    /// Rejects non-canonical encodings, i.e. bool words other than 0 or 1,
+    /// dirty high bits in addresses and `uint<M>` values, nonzero padding
+    /// after `bytes<M>` and `bytes` values, and offsets pointing backwards or
+    /// into previously decoded data. `int<M>` values that are not sign
+    /// extended to 256 bits are rejected in all modes.
+    pub strict: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            max_elements: None,
+            max_depth: 128,
+            max_output_bytes: None,
+            strict: false,
+        }
+    }
+}
+
+// Decoding state used to enforce decode options.
+struct DecodeContext<'a> {
+    options: &'a DecodeOptions,
+    max_elements: usize,
+    max_output_bytes: usize,
+    elements: usize,
+    output_bytes: usize,
+    // End of the furthest input data read so far, used to detect offsets
+    // pointing backwards in strict mode.
+    read_end: usize,
+}
+
+impl<'a> DecodeContext<'a> {
+    fn new(options: &'a DecodeOptions, input_len: usize) -> Self {
+        Self {
+            options,
+            max_elements: options.max_elements.unwrap_or(input_len),
+            max_output_bytes: options.max_output_bytes.unwrap_or(input_len),
+            elements: 0,
+            output_bytes: 0,
+            read_end: 0,
+        }
+    }
+
+    // Reads `len` bytes at the given offset.
+    fn read<'b>(&mut self, bs: &'b [u8], at: usize, len: usize) -> Result<&'b [u8]> {
+        let end = at
+            .checked_add(len)
+            .filter(|end| *end <= bs.len())
+            .ok_or(Error::InputTooShort { needed: len, at })?;
+
+        self.read_end = self.read_end.max(end);
+
+        Ok(&bs[at..end])
+    }
+
+    // Reads a 32 bytes offset or length word at the given offset.
+    fn read_usize(&mut self, bs: &[u8], at: usize) -> Result<usize> {
+        let word = U256::from_big_endian(self.read(bs, at, 32)?);
+
+        if word > U256::from(usize::MAX) {
+            return Err(Error::InvalidOffset(word));
+        }
+
+        Ok(word.as_usize())
+    }
+
+    // Reads an offset word at `at` and returns the address it points to
+    // relative to `base_addr`.
+    fn read_offset(&mut self, bs: &[u8], base_addr: usize, at: usize) -> Result<usize> {
+        let offset = self.read_usize(bs, Value::offset(base_addr, at)?)?;
+        let addr = Value::offset(base_addr, offset)?;
+
+        // canonical encodings place data after everything decoded before it
+        self.check(addr >= self.read_end, "offset points backwards")?;
+
+        Ok(addr)
+    }
+
+    // Marks the `len` bytes head of a sequence of values as read, so that
+    // offsets cannot point into it.
+    fn read_head(&mut self, at: usize, len: usize) {
+        self.read_end = self.read_end.max(at.saturating_add(len));
+    }
+
+    // Fails with the given reason if `canonical` is false in strict mode.
+    fn check(&self, canonical: bool, reason: &'static str) -> Result<()> {
+        if self.options.strict && !canonical {
+            return Err(Error::NonCanonical(reason));
+        }
+
+        Ok(())
+    }
+
+    // Checks that `count` more elements can be decoded without exceeding the limit.
+    fn check_elements(&self, count: usize) -> Result<()> {
+        if self.elements.saturating_add(count) > self.max_elements {
+            return Err(Error::LimitExceeded("max_elements"));
+        }
+
+        Ok(())
+    }
+
+    fn add_element(&mut self) -> Result<()> {
+        self.check_elements(1)?;
+        self.elements += 1;
+
+        Ok(())
+    }
+
+    fn check_depth(&self, depth: usize) -> Result<()> {
+        if depth > self.options.max_depth {
+            return Err(Error::LimitExceeded("max_depth"));
+        }
+
+        Ok(())
+    }
+
+    fn add_output_bytes(&mut self, len: usize) -> Result<()> {
+        self.output_bytes = self.output_bytes.saturating_add(len);
+
+        if self.output_bytes > self.max_output_bytes {
+            return Err(Error::LimitExceeded("max_output_bytes"));
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |values: &mut dyn Iterator<Item = &Value>| {
+            values
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            Value::Uint(i, _) => write!(f, "{}", i),
+            Value::Int(_, _) => {
+                let (negative, abs) = self.int_abs().unwrap_or_default();
+
+                write!(f, "{}{}", if negative { "-" } else { "" }, abs)
+            }
+            Value::UFixed(i, _, decimals) => write!(f, "{}", format_fixed(*i, *decimals)),
+            Value::Fixed(_, _, decimals) => {
+                let (negative, abs) = self.int_abs().unwrap_or_default();
+
+                write!(
+                    f,
+                    "{}{}",
+                    if negative { "-" } else { "" },
+                    format_fixed(abs, *decimals)
+                )
+            }
+            Value::Address(addr) => write!(f, "{:?}", addr),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Function { address, selector } => {
+                write!(f, "{:?}{}", address, hex::encode(selector))
+            }
+            Value::FixedBytes(bytes) | Value::Bytes(bytes) => {
+                write!(f, "0x{}", hex::encode(bytes))
+            }
+            Value::String(s) => write!(f, "{:?}", s),
+            Value::FixedArray(values, _) | Value::Array(values, _) => {
+                write!(f, "[{}]", join(&mut values.iter()))
+            }
+            Value::Tuple(values) => {
+                write!(f, "({})", join(&mut values.iter().map(|(_, value)| value)))
+            }
+        }
+    }
+}
+
+// Formats an integer scaled by 10^decimals as a decimal number, e.g. 1.50 for
+// 150 with 2 decimals.
+fn format_fixed(i: U256, decimals: usize) -> String {
+    let digits = format!("{:0>width$}", i.to_string(), width = decimals + 1);
+    let (int, frac) = digits.split_at(digits.len() - decimals);
+
+    if frac.is_empty() {
+        int.to_string()
+    } else {
+        format!("{}.{}", int, frac)
+    }
+}
+
+// Sign extends the lowest `size` bits of a two's complement integer to 256 bits.
+fn sign_extend(i: U256, size: usize) -> U256 {
+    if size == 0 || size >= 256 {
+        return i;
+    }
+
+    let mask = (U256::one() << size) - 1;
+    let low = i & mask;
+
+    if low.bit(size - 1) {
+        low | !mask
+    } else {
+        low
+    }
+}
+
+// Negates the given 256 bits integer, in two's complement, if `negate` is true.
+pub(crate) fn twos_complement(negate: bool, i: U256) -> U256 {
+    if negate {
+        (!i).overflowing_add(U256::one()).0
+    } else {
+        i
+    }
+}
+
+// Size of a value's encoding in the head of its enclosing sequence.
+fn head_size(ty: &Type) -> usize {
+    match ty {
+        _ if ty.is_dynamic() => 32,
+        Type::FixedArray(ty, size) => head_size(ty).saturating_mul(*size),
+        Type::Tuple(tys) => heads_size(tys.iter().map(|(_, ty)| ty)),
+        _ => 32,
+    }
+}
+
+// Whether the type is or contains an empty tuple or a zero length fixed
+// array, neither of which Solidity allows.
+fn has_zero_size(ty: &Type) -> bool {
+    match ty {
+        Type::Array(ty) => has_zero_size(ty),
+        Type::FixedArray(ty, size) => *size == 0 || has_zero_size(ty),
+        Type::Tuple(tys) => tys.is_empty() || tys.iter().any(|(_, ty)| has_zero_size(ty)),
+        _ => false,
+    }
+}
+
+fn heads_size<'a>(tys: impl IntoIterator<Item = &'a Type>) -> usize {
+    tys.into_iter()
+        .fold(0, |len, ty| len.saturating_add(head_size(ty)))
+}
+
 impl Value {
     /// Decodes values from bytes using the given type hint.
     pub fn decode_from_slice(bs: &[u8], tys: &[Type]) -> Result<Vec<Value>> {
+        Self::decode_from_slice_with(bs, tys, &DecodeOptions::default())
+    }
+
+    /// Decodes values from bytes using the given type hint and decode options.
+    pub fn decode_from_slice_with(
+        bs: &[u8],
+        tys: &[Type],
+        options: &DecodeOptions,
+    ) -> Result<Vec<Value>> {
+        let mut ctx = DecodeContext::new(options, bs.len());
+        ctx.read_head(0, heads_size(tys));
+
         tys.iter()
             .try_fold((vec![], 0), |(mut values, at), ty| {
-                let (value, consumed) = Self::decode(bs, ty, 0, at)?;
+                let (value, consumed) = Self::decode(&mut ctx, bs, ty, 0, at, 0)?;
                 values.push(value);
 
                 Ok((values, at + consumed))
@@ -44,19 +333,32 @@ impl Value {
     }
 
     /// Encodes values into bytes.
+    ///
+    /// Values are not checked against their types, see [`Value::try_encode`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if a fixed size bytes value is longer than 32 bytes.
     pub fn encode(values: &[Self]) -> Vec<u8> {
         let mut buf = vec![];
         let mut alloc_queue = std::collections::VecDeque::new();
 
         for value in values {
             match value {
-                Value::Uint(i, _) | Value::Int(i, _) => {
+                Value::Uint(i, _) | Value::UFixed(i, _, _) => {
                     let start = buf.len();
                     buf.resize(buf.len() + 32, 0);
 
                     i.to_big_endian(&mut buf[start..(start + 32)]);
                 }
 
+                Value::Int(i, size) | Value::Fixed(i, size, _) => {
+                    let start = buf.len();
+                    buf.resize(buf.len() + 32, 0);
+
+                    sign_extend(*i, *size).to_big_endian(&mut buf[start..(start + 32)]);
+                }
+
                 Value::Address(addr) => {
                     let start = buf.len();
                     buf.resize(buf.len() + 32, 0);
@@ -65,6 +367,15 @@ impl Value {
                     buf[(start + 12)..(start + 32)].copy_from_slice(addr.as_fixed_bytes());
                 }
 
+                Value::Function { address, selector } => {
+                    let start = buf.len();
+                    buf.resize(buf.len() + 32, 0);
+
+                    // left-aligned, as if it were a bytes24.
+                    buf[start..(start + 20)].copy_from_slice(address.as_bytes());
+                    buf[(start + 20)..(start + 24)].copy_from_slice(selector);
+                }
+
                 Value::Bool(b) => {
                     let start = buf.len();
                     buf.resize(buf.len() + 32, 0);
@@ -78,7 +389,7 @@ impl Value {
                     let start = buf.len();
                     buf.resize(buf.len() + 32, 0);
 
-                    buf[start..(start + bytes.len())].copy_from_slice(&bytes);
+                    buf[start..(start + bytes.len())].copy_from_slice(bytes);
                 }
 
                 Value::FixedArray(values, _) => {
@@ -153,23 +464,250 @@ impl Value {
                     buf.extend(bytes);
                 }
 
-                _ => panic!(format!(
-                    "value of fixed size type {:?} in dynamic alloc area",
-                    value
-                )),
+                _ => panic!("value of fixed size type {:?} in dynamic alloc area", value),
             };
         }
 
         buf
     }
 
+    /// Encodes values into bytes, after checking that every value is valid for
+    /// its type (see [`Value::type_check`]).
+    pub fn try_encode(values: &[Self]) -> Result<Vec<u8>> {
+        for value in values {
+            value.type_check(&value.type_of())?;
+        }
+
+        Ok(Self::encode(values))
+    }
+
+    /// Checks that the value has the given type and is valid for it, i.e. that
+    /// ints fit in their size, fixed size bytes and arrays have the right
+    /// length and array elements all have the array's element type.
+    ///
+    /// Tuple component names are ignored.
+    pub fn type_check(&self, ty: &Type) -> Result<()> {
+        let invalid = |msg: String| Err(Error::InvalidValue(msg));
+
+        match (self, ty) {
+            (Value::Uint(i, size), Type::Uint(ty_size)) if size == ty_size => {
+                if !Self::valid_int_size(*size) || i.bits() > *size {
+                    return invalid(format!("{} does not fit in uint{}", i, size));
+                }
+            }
+
+            (Value::Int(i, size), Type::Int(ty_size)) if size == ty_size => {
+                if !Self::valid_int_size(*size) || sign_extend(*i, *size) != *i {
+                    return invalid(format!("{} does not fit in int{}", self, size));
+                }
+            }
+
+            (Value::UFixed(i, size, decimals), Type::UFixed(ty_size, ty_decimals))
+                if size == ty_size && decimals == ty_decimals =>
+            {
+                if !Self::valid_fixed_size(*size, *decimals) || i.bits() > *size {
+                    return invalid(format!("{} does not fit in {}", self, ty));
+                }
+            }
+
+            (Value::Fixed(i, size, decimals), Type::Fixed(ty_size, ty_decimals))
+                if size == ty_size && decimals == ty_decimals =>
+            {
+                if !Self::valid_fixed_size(*size, *decimals) || sign_extend(*i, *size) != *i {
+                    return invalid(format!("{} does not fit in {}", self, ty));
+                }
+            }
+
+            (Value::Address(_), Type::Address)
+            | (Value::Bool(_), Type::Bool)
+            | (Value::Function { .. }, Type::Function)
+            | (Value::String(_), Type::String)
+            | (Value::Bytes(_), Type::Bytes) => {}
+
+            (Value::FixedBytes(bytes), Type::FixedBytes(size)) => {
+                if bytes.len() != *size || *size == 0 || *size > 32 {
+                    return invalid(format!("{} bytes value for type {}", bytes.len(), ty));
+                }
+            }
+
+            (_, Type::FixedArray(_, 0)) => return invalid(format!("zero-size type {}", ty)),
+
+            (Value::FixedArray(values, elem_ty), Type::FixedArray(ty_elem_ty, size)) => {
+                if values.len() != *size {
+                    return invalid(format!("{} elements for type {}", values.len(), ty));
+                }
+
+                Self::type_check_elements(values, elem_ty, ty_elem_ty)?;
+            }
+
+            (Value::Array(values, elem_ty), Type::Array(ty_elem_ty)) => {
+                // elements check their type, empty arrays need checking here
+                if values.is_empty() && has_zero_size(ty_elem_ty) {
+                    return invalid(format!("zero-size type {}", ty_elem_ty));
+                }
+
+                Self::type_check_elements(values, elem_ty, ty_elem_ty)?;
+            }
+
+            (_, Type::Tuple(tys)) if tys.is_empty() => {
+                return invalid(format!("zero-size type {}", ty))
+            }
+
+            (Value::Tuple(values), Type::Tuple(tys)) if values.len() == tys.len() => {
+                for ((_, value), (_, ty)) in values.iter().zip(tys) {
+                    value.type_check(ty)?;
+                }
+            }
+
+            _ => return invalid(format!("expected {}, got {}", ty, self.type_of())),
+        }
+
+        Ok(())
+    }
+
+    // Checks array elements against both the value's and the declared element type.
+    fn type_check_elements(values: &[Value], elem_ty: &Type, ty_elem_ty: &Type) -> Result<()> {
+        // compare canonical type strings so that tuple component names are ignored
+        if elem_ty.to_string() != ty_elem_ty.to_string() {
+            return Err(Error::InvalidValue(format!(
+                "expected {}[], got {}[]",
+                ty_elem_ty, elem_ty
+            )));
+        }
+
+        values
+            .iter()
+            .try_for_each(|value| value.type_check(ty_elem_ty))
+    }
+
+    fn valid_int_size(size: usize) -> bool {
+        size > 0 && size <= 256 && size.is_multiple_of(8)
+    }
+
+    fn valid_fixed_size(size: usize, decimals: usize) -> bool {
+        Self::valid_int_size(size) && decimals <= 80
+    }
+
+    /// Encodes values using Solidity's non-standard packed mode (`abi.encodePacked`).
+    ///
+    /// Top-level values are encoded without padding, while array elements are
+    /// padded to 32 bytes. Tuples and arrays of dynamic types are not supported.
+    /// Fails if a value is not valid for its type (see [`Value::type_check`]).
+    pub fn encode_packed(values: &[Self]) -> Result<Vec<u8>> {
+        let mut buf = vec![];
+
+        for value in values {
+            value.type_check(&value.type_of())?;
+
+            match value {
+                Value::Uint(i, size)
+                | Value::Int(i, size)
+                | Value::UFixed(i, size, _)
+                | Value::Fixed(i, size, _) => {
+                    let mut word = [0u8; 32];
+                    i.to_big_endian(&mut word);
+
+                    buf.extend(&word[(32 - size / 8)..]);
+                }
+
+                Value::Address(addr) => buf.extend(addr.as_bytes()),
+
+                Value::Function { address, selector } => {
+                    buf.extend(address.as_bytes());
+                    buf.extend(selector);
+                }
+
+                Value::Bool(b) => buf.push(*b as u8),
+
+                Value::FixedBytes(bytes) | Value::Bytes(bytes) => buf.extend(bytes),
+
+                Value::String(s) => buf.extend(s.as_bytes()),
+
+                Value::FixedArray(values, ty) | Value::Array(values, ty) => {
+                    if ty.is_dynamic() || matches!(ty, Type::Tuple(_)) {
+                        return Err(Error::Unsupported(format!(
+                            "packed encoding of arrays of type {}",
+                            ty
+                        )));
+                    }
+
+                    // array elements are padded as in the standard encoding
+                    buf.extend(Self::encode(values));
+                }
+
+                Value::Tuple(_) => {
+                    return Err(Error::Unsupported("packed encoding of tuples".to_string()));
+                }
+            }
+        }
+
+        Ok(buf)
+    }
+
+    /// Creates a signed int value (int<M>) from the given integer.
+    ///
+    /// Fails if the integer does not fit in `int<size>` or the size is invalid.
+    pub fn from_i64(i: i64, size: usize) -> Result<Self> {
+        let value = Value::Int(twos_complement(i < 0, U256::from(i.unsigned_abs())), size);
+        value.type_check(&Type::Int(size))?;
+
+        Ok(value)
+    }
+
+    /// Returns whether the value is a negative signed int.
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Value::Int(i, size) | Value::Fixed(i, size, _) => sign_extend(*i, *size).bit(255),
+            _ => false,
+        }
+    }
+
+    /// Converts an int or uint value into an `i128`.
+    ///
+    /// Returns `None` for other values or if the value does not fit.
+    pub fn to_i128(&self) -> Option<i128> {
+        let (negative, abs) = match self {
+            Value::Uint(i, _) => (false, *i),
+            Value::Int(_, _) => self.int_abs()?,
+            _ => return None,
+        };
+
+        let abs = if abs.bits() <= 128 {
+            abs.as_u128()
+        } else {
+            return None;
+        };
+
+        if negative {
+            0i128.checked_sub_unsigned(abs)
+        } else {
+            Some(abs as i128).filter(|i| *i >= 0)
+        }
+    }
+
+    // Returns the sign and absolute value of a signed int or fixed value.
+    fn int_abs(&self) -> Option<(bool, U256)> {
+        match self {
+            Value::Int(i, size) | Value::Fixed(i, size, _) => {
+                let i = sign_extend(*i, *size);
+                let negative = i.bit(255);
+
+                Some((negative, twos_complement(negative, i)))
+            }
+            _ => None,
+        }
+    }
+
     /// Returns the type of the given value.
     pub fn type_of(&self) -> Type {
         match self {
             Value::Uint(_, size) => Type::Uint(*size),
             Value::Int(_, size) => Type::Int(*size),
+            Value::UFixed(_, size, decimals) => Type::UFixed(*size, *decimals),
+            Value::Fixed(_, size, decimals) => Type::Fixed(*size, *decimals),
             Value::Address(_) => Type::Address,
             Value::Bool(_) => Type::Bool,
+            Value::Function { .. } => Type::Function,
             Value::FixedBytes(bytes) => Type::FixedBytes(bytes.len()),
             Value::FixedArray(values, ty) => Type::FixedArray(Box::new(ty.clone()), values.len()),
             Value::String(_) => Type::String,
@@ -184,98 +722,147 @@ impl Value {
         }
     }
 
-    fn decode(bs: &[u8], ty: &Type, base_addr: usize, at: usize) -> Result<(Value, usize)> {
+    fn decode(
+        ctx: &mut DecodeContext,
+        bs: &[u8],
+        ty: &Type,
+        base_addr: usize,
+        at: usize,
+        depth: usize,
+    ) -> Result<(Value, usize)> {
+        ctx.check_depth(depth)?;
+
+        // static tuples and fixed arrays take no input of their own, their
+        // members are counted instead
+        if !matches!(ty, Type::Tuple(_) | Type::FixedArray(..)) || ty.is_dynamic() {
+            ctx.add_element()?;
+        }
+
         match ty {
-            Type::Uint(size) => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 32))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding uint{}", size))?;
+            Type::Uint(size) | Type::UFixed(size, _) => {
+                let at = Self::offset(base_addr, at)?;
+                let slice = ctx.read(bs, at, 32)?;
+                ctx.add_output_bytes(32)?;
 
                 let uint = U256::from_big_endian(slice);
+                ctx.check(
+                    *size >= 256 || (uint >> *size).is_zero(),
+                    "dirty uint high bits",
+                )?;
+
+                let value = match ty {
+                    Type::UFixed(_, decimals) => Value::UFixed(uint, *size, *decimals),
+                    _ => Value::Uint(uint, *size),
+                };
 
-                Ok((Value::Uint(uint, *size), 32))
+                Ok((value, 32))
             }
 
-            Type::Int(size) => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 32))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding int{}", size))?;
+            Type::Int(size) | Type::Fixed(size, _) => {
+                let at = Self::offset(base_addr, at)?;
+                let slice = ctx.read(bs, at, 32)?;
+                ctx.add_output_bytes(32)?;
 
                 let uint = U256::from_big_endian(slice);
+                let int = sign_extend(uint, *size);
+
+                // the bits above the int size must be its sign extension
+                if int != uint {
+                    return Err(Error::InvalidValue(format!(
+                        "{:#x} is not a sign extended {}",
+                        uint, ty
+                    )));
+                }
+
+                let value = match ty {
+                    Type::Fixed(_, decimals) => Value::Fixed(int, *size, *decimals),
+                    _ => Value::Int(int, *size),
+                };
 
-                Ok((Value::Int(uint, *size), 32))
+                Ok((value, 32))
             }
 
             Type::Address => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get((at + 12)..(at + 32))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding address"))?;
+                let at = Self::offset(base_addr, at)?;
+                let slice = ctx.read(bs, at, 32)?;
+                ctx.add_output_bytes(32)?;
+                ctx.check(
+                    slice[..12].iter().all(|b| *b == 0),
+                    "dirty address high bits",
+                )?;
 
                 // big-endian, same as if it were a uint160.
-                let addr = H160::from_slice(slice);
+                let addr = H160::from_slice(&slice[12..]);
 
                 Ok((Value::Address(addr), 32))
             }
 
+            Type::Function => {
+                let at = Self::offset(base_addr, at)?;
+                let slice = ctx.read(bs, at, 32)?;
+                ctx.add_output_bytes(32)?;
+                ctx.check(
+                    slice[24..].iter().all(|b| *b == 0),
+                    "dirty function padding",
+                )?;
+
+                let address = H160::from_slice(&slice[..20]);
+                let mut selector = [0u8; 4];
+                selector.copy_from_slice(&slice[20..24]);
+
+                Ok((Value::Function { address, selector }, 32))
+            }
+
             Type::Bool => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 32))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding bool"))?;
+                let at = Self::offset(base_addr, at)?;
+                let slice = ctx.read(bs, at, 32)?;
+                ctx.add_output_bytes(32)?;
 
-                let b = U256::from_big_endian(slice) == U256::one();
+                let word = U256::from_big_endian(slice);
+                ctx.check(word <= U256::one(), "bool is not 0 or 1")?;
+
+                let b = word == U256::one();
 
                 Ok((Value::Bool(b), 32))
             }
 
             Type::FixedBytes(size) => {
-                let at = base_addr + at;
-                let bv = bs
-                    .get(at..(at + size))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding bytes{}", size))?
-                    .to_vec();
+                let at = Self::offset(base_addr, at)?;
+                let bv = ctx.read(bs, at, *size)?.to_vec();
+                ctx.add_output_bytes(*size)?;
+
+                if ctx.options.strict {
+                    let padding = ctx.read(bs, at, 32)?.get(*size..).unwrap_or_default();
+                    ctx.check(padding.iter().all(|b| *b == 0), "dirty bytes padding")?;
+                }
 
                 Ok((Value::FixedBytes(bv), Self::padded32_size(*size)))
             }
 
-            Type::FixedArray(ty, size) => {
-                let (base_addr, at) = if ty.is_dynamic() {
+            Type::FixedArray(elem_ty, size) => {
+                if ty.is_dynamic() {
                     // For fixed arrays of types that are dynamic, we just jump
                     // to the offset location and decode from there.
-                    let slice = bs.get(at..(at + 32)).ok_or_else(|| {
-                        anyhow!("reached end of input while decoding {}[{}]", ty, size)
-                    })?;
-                    let offset = U256::from_big_endian(slice).as_usize();
+                    let base_addr = ctx.read_offset(bs, base_addr, at)?;
+
+                    let (values, _) =
+                        Self::decode_sequence(ctx, bs, elem_ty, *size, base_addr, 0, depth + 1)?;
 
-                    (base_addr + offset, 0)
+                    Ok((Value::FixedArray(values, *elem_ty.clone()), 32))
                 } else {
                     // There's no need to change the addressing because fixed arrays
                     // will consume input by calling decode recursively and addressing
                     // will be computed correctly inside those calls.
-                    (base_addr, at)
-                };
-
-                (0..(*size))
-                    .try_fold((vec![], 0), |(mut values, total_consumed), _| {
-                        let (value, consumed) =
-                            Self::decode(bs, ty, base_addr, at + total_consumed)?;
-
-                        values.push(value);
-
-                        Ok((values, total_consumed + consumed))
-                    })
-                    .map(|(values, consumed)| {
-                        let consumed = if ty.is_dynamic() { 32 } else { consumed };
+                    let (values, consumed) =
+                        Self::decode_sequence(ctx, bs, elem_ty, *size, base_addr, at, depth + 1)?;
 
-                        (Value::FixedArray(values, *ty.clone()), consumed)
-                    })
+                    Ok((Value::FixedArray(values, *elem_ty.clone()), consumed))
+                }
             }
 
             Type::String => {
-                let (bytes_value, consumed) = Self::decode(bs, &Type::Bytes, base_addr, at)?;
+                let (bytes_value, consumed) =
+                    Self::decode(ctx, bs, &Type::Bytes, base_addr, at, depth)?;
 
                 let bytes = if let Value::Bytes(bytes) = bytes_value {
                     bytes
@@ -290,72 +877,60 @@ impl Value {
             }
 
             Type::Bytes => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 32))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding bytes offset"))?;
-                let offset = U256::from_big_endian(slice).as_usize();
-
-                let at = base_addr + offset;
-
-                let slice = bs
-                    .get(at..(at + 32))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding bytes length"))?;
-                let bytes_len = U256::from_big_endian(slice).as_usize();
-
-                let at = at + 32;
-                let bytes = bs
-                    .get(at..(at + bytes_len))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding bytes"))?
-                    .to_vec();
+                let at = ctx.read_offset(bs, base_addr, at)?;
+                let bytes_len = ctx.read_usize(bs, at)?;
+
+                let at = Self::offset(at, 32)?;
+                let bytes = ctx.read(bs, at, bytes_len)?;
+                ctx.add_output_bytes(bytes_len)?;
+                let bytes = bytes.to_vec();
+
+                if ctx.options.strict {
+                    let padded = ctx.read(bs, at, Self::padded32_size(bytes_len))?;
+                    let padding = &padded[bytes_len..];
+                    ctx.check(padding.iter().all(|b| *b == 0), "dirty bytes padding")?;
+                }
 
                 // consumes only the first 32 bytes, i.e. the offset pointer
                 Ok((Value::Bytes(bytes), 32))
             }
 
-            Type::Array(ty) => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 32))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding array offset"))?;
-                let offset = U256::from_big_endian(slice).as_usize();
-
-                let at = base_addr + offset;
-                let slice = bs
-                    .get(at..(at + 32))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding array length"))?;
-                let array_len = U256::from_big_endian(slice).as_usize();
-
-                let (arr, _) = Self::decode(bs, &Type::FixedArray(ty.clone(), array_len), at, 32)?;
+            Type::Array(elem_ty) => {
+                let at = ctx.read_offset(bs, base_addr, at)?;
+                let array_len = ctx.read_usize(bs, at)?;
 
-                let values = if let Value::FixedArray(values, _) = arr {
-                    values
-                } else {
-                    // should always be Value::FixedArray
-                    unreachable!();
-                };
+                // array elements are encoded right after the array length
+                let base_addr = Self::offset(at, 32)?;
+                let (values, _) =
+                    Self::decode_sequence(ctx, bs, elem_ty, array_len, base_addr, 0, depth + 1)?;
 
-                Ok((Value::Array(values, *ty.clone()), 32))
+                Ok((Value::Array(values, *elem_ty.clone()), 32))
             }
 
             Type::Tuple(tys) => {
                 // Tuples follow the same logic as fixed arrays.
                 let (base_addr, at) = if ty.is_dynamic() {
-                    let slice = bs.get(at..(at + 32)).ok_or_else(|| {
-                        anyhow!("reached end of input while decoding tuple offset")
-                    })?;
-                    let offset = U256::from_big_endian(slice).as_usize();
-
-                    (base_addr + offset, 0)
+                    (ctx.read_offset(bs, base_addr, at)?, 0)
                 } else {
                     (base_addr, at)
                 };
 
+                ctx.read_head(
+                    base_addr.saturating_add(at),
+                    heads_size(tys.iter().map(|(_, ty)| ty)),
+                );
+
                 tys.iter()
                     .cloned()
                     .try_fold((vec![], 0), |(mut values, total_consumed), (name, ty)| {
-                        let (value, consumed) =
-                            Self::decode(bs, &ty, base_addr, at + total_consumed)?;
+                        let (value, consumed) = Self::decode(
+                            ctx,
+                            bs,
+                            &ty,
+                            base_addr,
+                            Self::offset(at, total_consumed)?,
+                            depth + 1,
+                        )?;
 
                         values.push((name, value));
 
@@ -370,6 +945,56 @@ impl Value {
         }
     }
 
+    // Decodes `count` consecutive values of the given type.
+    fn decode_sequence(
+        ctx: &mut DecodeContext,
+        bs: &[u8],
+        ty: &Type,
+        count: usize,
+        base_addr: usize,
+        at: usize,
+        depth: usize,
+    ) -> Result<(Vec<Value>, usize)> {
+        let elem_size = head_size(ty);
+
+        // zero-size elements consume no input, so nothing bounds their count
+        if elem_size == 0 && count > 0 {
+            return Err(Error::Unsupported(format!("zero-size type {}", ty)));
+        }
+
+        // fail early on huge (possibly malicious) array lengths
+        let start = Self::offset(base_addr, at)?;
+        let needed = elem_size.saturating_mul(count);
+        if needed > bs.len().saturating_sub(start) {
+            return Err(Error::InputTooShort { needed, at: start });
+        }
+
+        ctx.check_elements(count)?;
+        ctx.read_head(start, needed);
+
+        (0..count).try_fold((vec![], 0), |(mut values, total_consumed), _| {
+            let (value, consumed) = Self::decode(
+                ctx,
+                bs,
+                ty,
+                base_addr,
+                Self::offset(at, total_consumed)?,
+                depth,
+            )?;
+
+            values.push(value);
+
+            Ok((values, total_consumed + consumed))
+        })
+    }
+
+    // Adds an offset to a base address, failing on overflow.
+    fn offset(base_addr: usize, offset: usize) -> Result<usize> {
+        base_addr
+            .checked_add(offset)
+            .ok_or_else(|| Error::InvalidOffset(U256::from(base_addr) + U256::from(offset)))
+    }
+
     fn encode_bytes(buf: &mut Vec<u8>, bytes: &[u8], mut alloc_offset: usize) -> usize {
         let padded_bytes_len = Self::padded32_size(bytes.len());
         buf.resize(buf.len() + 32 + padded_bytes_len, 0);
@@ -388,7 +1013,7 @@ impl Value {
     // padded32_size(20) == 32
     // padded32_size(32) == 32
     // padded32_size(40) == 64
-    fn padded32_size(size: usize) -> usize {
+    pub(crate) fn padded32_size(size: usize) -> usize {
         let r = size % 32;
 
         if r == 0 {
@@ -693,6 +1318,378 @@ mod test {
         );
     }
 
+    #[test]
+    fn decode_errors() {
+        let mut bs = [0u8; 96];
+        bs[31] = 0x20; // big-endian string offset
+        bs[63] = 2; // big-endian string size
+        bs[64] = 0xff;
+        bs[65] = 0xfe;
+
+        assert!(matches!(
+            Value::decode_from_slice(&bs, &[Type::String]),
+            Err(Error::InvalidUtf8(_))
+        ));
+        assert!(matches!(
+            Value::decode_from_slice(&bs[0..40], &[Type::Uint(256), Type::Bool]),
+            Err(Error::InputTooShort { needed: 32, at: 32 })
+        ));
+    }
+
+    #[test]
+    fn decode_dynamic_arrays() {
+        let values = vec![
+            Value::Array(
+                vec![
+                    Value::String("a".to_string()),
+                    Value::String("bc".to_string()),
+                ],
+                Type::String,
+            ),
+            Value::Tuple(vec![
+                ("x".to_string(), Value::Uint(U256::from(7), 256)),
+                (
+                    "y".to_string(),
+                    Value::Array(
+                        vec![Value::Array(
+                            vec![Value::Uint(U256::from(3), 8)],
+                            Type::Uint(8),
+                        )],
+                        Type::Array(Box::new(Type::Uint(8))),
+                    ),
+                ),
+            ]),
+        ];
+
+        let tys: Vec<_> = values.iter().map(Value::type_of).collect();
+
+        assert_eq!(
+            Value::decode_from_slice(&Value::encode(&values), &tys)
+                .expect("decode_from_slice failed"),
+            values
+        );
+
+        let strict = DecodeOptions {
+            strict: true,
+            ..DecodeOptions::default()
+        };
+
+        assert_eq!(
+            Value::decode_from_slice_with(&Value::encode(&values), &tys, &strict)
+                .expect("strict decode_from_slice_with failed"),
+            values
+        );
+    }
+
+    #[test]
+    fn decode_strict() {
+        let strict = DecodeOptions {
+            strict: true,
+            ..DecodeOptions::default()
+        };
+
+        let word = |bytes: &[(usize, u8)]| {
+            let mut bs = vec![0u8; 32];
+            bytes.iter().for_each(|(i, b)| bs[*i] = *b);
+            bs
+        };
+
+        let cases = vec![
+            (word(&[(31, 2)]), Type::Bool),
+            (word(&[(0, 1), (31, 1)]), Type::Bool),
+            (word(&[(11, 1)]), Type::Address),
+            (word(&[(30, 1)]), Type::Uint(8)),
+            (word(&[(0, 0x80)]), Type::Uint(255)),
+            (word(&[(0, 0xab), (2, 0xcd)]), Type::FixedBytes(2)),
+            (word(&[(24, 1)]), Type::Function),
+            // padding after the bytes data
+            (
+                [
+                    word(&[(31, 0x20)]),
+                    word(&[(31, 1)]),
+                    word(&[(0, 1), (1, 1)]),
+                ]
+                .concat(),
+                Type::Bytes,
+            ),
+            // offset pointing at itself
+            (
+                [word(&[]), word(&[(31, 1)]), word(&[(0, 1)])].concat(),
+                Type::Bytes,
+            ),
+            // both elements pointing at the same data
+            (
+                [
+                    word(&[(31, 0x20)]),
+                    word(&[(31, 0x40)]),
+                    word(&[(31, 0x40)]),
+                    word(&[(31, 1)]),
+                    word(&[(0, 1)]),
+                ]
+                .concat(),
+                Type::FixedArray(Box::new(Type::Bytes), 2),
+            ),
+        ];
+
+        for (bs, ty) in cases {
+            let tys = [ty];
+
+            assert!(
+                Value::decode_from_slice(&bs, &tys).is_ok(),
+                "{} should decode",
+                tys[0]
+            );
+            assert!(
+                matches!(
+                    Value::decode_from_slice_with(&bs, &tys, &strict),
+                    Err(Error::NonCanonical(_))
+                ),
+                "{} should not decode in strict mode",
+                tys[0]
+            );
+        }
+    }
+
+    #[test]
+    fn decode_invalid_offsets() {
+        // offset word above usize::MAX
+        let bs = [0xffu8; 32];
+
+        for ty in &[
+            Type::Bytes,
+            Type::String,
+            Type::Array(Box::new(Type::Uint(256))),
+            Type::FixedArray(Box::new(Type::String), 2),
+            Type::Tuple(vec![("a".to_string(), Type::Bytes)]),
+        ] {
+            assert!(matches!(
+                Value::decode_from_slice(&bs, std::slice::from_ref(ty)),
+                Err(Error::InvalidOffset(_))
+            ));
+        }
+
+        // offset and length words that overflow when added to the base address
+        let mut bs = [0u8; 96];
+        let max = U256::from(usize::MAX);
+        bs[31] = 0x20;
+        max.to_big_endian(&mut bs[32..64]);
+
+        assert!(Value::decode_from_slice(&bs, &[Type::Bytes]).is_err());
+        assert!(Value::decode_from_slice(&bs, &[Type::Array(Box::new(Type::Uint(8)))]).is_err());
+
+        (max - 10).to_big_endian(&mut bs[0..32]);
+
+        assert!(Value::decode_from_slice(&bs, &[Type::Bytes]).is_err());
+    }
+
+    #[test]
+    fn decode_with_limits() {
+        let values = vec![Value::Array(
+            vec![Value::Uint(U256::one(), 8); 3],
+            Type::Uint(8),
+        )];
+        let bs = Value::encode(&values);
+
+        let options = DecodeOptions {
+            max_elements: Some(3),
+            ..DecodeOptions::default()
+        };
+
+        assert!(matches!(
+            Value::decode_from_slice_with(&bs, &[values[0].type_of()], &options),
+            Err(Error::LimitExceeded("max_elements"))
+        ));
+
+        let values = vec![Value::Array(
+            vec![Value::Array(vec![Value::Bool(true)], Type::Bool)],
+            Type::Array(Box::new(Type::Bool)),
+        )];
+        let tys = vec![values[0].type_of()];
+        let bs = Value::encode(&values);
+
+        let options = DecodeOptions {
+            max_depth: 1,
+            ..DecodeOptions::default()
+        };
+
+        assert!(matches!(
+            Value::decode_from_slice_with(&bs, &tys, &options),
+            Err(Error::LimitExceeded("max_depth"))
+        ));
+
+        let options = DecodeOptions {
+            max_depth: 2,
+            ..DecodeOptions::default()
+        };
+
+        assert_eq!(
+            Value::decode_from_slice_with(&bs, &tys, &options).unwrap(),
+            values
+        );
+
+        // both array elements point at the same 64 byte string
+        let mut bs = vec![0u8; 96];
+        bs[31] = 0x20;
+        bs[63] = 0x40;
+        bs[95] = 0x40;
+        bs.extend(Value::encode(&[Value::String("a".repeat(64))]).split_off(32));
+
+        let ty = Type::FixedArray(Box::new(Type::String), 2);
+        let options = DecodeOptions {
+            max_output_bytes: Some(100),
+            ..DecodeOptions::default()
+        };
+
+        assert!(Value::decode_from_slice(&bs, std::slice::from_ref(&ty)).is_ok());
+        assert!(matches!(
+            Value::decode_from_slice_with(&bs, &[ty], &options),
+            Err(Error::LimitExceeded("max_output_bytes"))
+        ));
+    }
+
+    #[test]
+    fn decode_default_limits() {
+        // uint256[][] whose 100 elements all point at the same 100 elements array
+        let n = 100;
+        let mut words = vec![U256::from(0x20), U256::from(n)];
+        words.extend(vec![U256::from(n * 32); n]);
+        words.push(U256::from(n));
+        words.extend((0..n).map(U256::from));
+
+        let bs: Vec<u8> = words
+            .iter()
+            .flat_map(|word| {
+                let mut buf = [0u8; 32];
+                word.to_big_endian(&mut buf);
+                buf.to_vec()
+            })
+            .collect();
+        let ty = Type::Array(Box::new(Type::Array(Box::new(Type::Uint(256)))));
+
+        assert!(matches!(
+            Value::decode_from_slice(&bs, std::slice::from_ref(&ty)),
+            Err(Error::LimitExceeded(_))
+        ));
+
+        let options = DecodeOptions {
+            max_elements: Some(usize::MAX),
+            max_output_bytes: Some(usize::MAX),
+            ..DecodeOptions::default()
+        };
+        let values = Value::decode_from_slice_with(&bs, &[ty], &options).unwrap();
+        assert!(matches!(&values[0], Value::Array(values, _) if values.len() == n));
+
+        // both string elements point at the same 256 bytes string
+        let mut bs = vec![0u8; 96];
+        bs[31] = 0x20;
+        bs[63] = 0x40;
+        bs[95] = 0x40;
+        bs.extend(Value::encode(&[Value::String("a".repeat(256))]).split_off(32));
+
+        let ty = Type::FixedArray(Box::new(Type::String), 2);
+        assert!(matches!(
+            Value::decode_from_slice(&bs, &[ty]),
+            Err(Error::LimitExceeded("max_output_bytes"))
+        ));
+
+        // canonical encodings decode within the default limits
+        let nested = (0..40).fold(Value::Bool(true), |value, _| {
+            Value::Tuple(vec![("a".to_string(), value)])
+        });
+        let bs = Value::encode(std::slice::from_ref(&nested));
+        assert_eq!(bs.len(), 32);
+        assert_eq!(
+            Value::decode_from_slice(&bs, &[nested.type_of()]).unwrap(),
+            vec![nested]
+        );
+
+        let values = vec![
+            Value::FixedBytes(vec![1]),
+            Value::Tuple(vec![(
+                "a".to_string(),
+                Value::Tuple(vec![("b".to_string(), Value::Bool(true))]),
+            )]),
+            Value::Array(
+                vec![Value::Bytes(vec![]), Value::Bytes(vec![1; 33])],
+                Type::Bytes,
+            ),
+        ];
+        let tys = values.iter().map(Value::type_of).collect::<Vec<_>>();
+
+        assert_eq!(
+            Value::decode_from_slice(&Value::encode(&values), &tys).unwrap(),
+            values
+        );
+    }
+
+    #[test]
+    fn decode_huge_array_length() {
+        // offset 0x20 followed by a u64::MAX array length
+        let mut bs = [0u8; 64];
+        bs[31] = 0x20;
+        U256::from(u64::MAX).to_big_endian(&mut bs[32..64]);
+
+        let ty = Type::Array(Box::new(Type::Uint(256)));
+        assert!(matches!(
+            Value::decode_from_slice(&bs, &[ty]),
+            Err(Error::InputTooShort { at: 64, .. })
+        ));
+
+        // zero-size elements consume no input
+        for ty in &[
+            Type::Array(Box::new(Type::Tuple(vec![]))),
+            Type::Array(Box::new(Type::FixedArray(Box::new(Type::Uint(8)), 0))),
+        ] {
+            assert!(matches!(
+                Value::decode_from_slice(&bs, std::slice::from_ref(ty)),
+                Err(Error::Unsupported(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn decode_random_input() {
+        let mut rng = rand::thread_rng();
+
+        let tys = [
+            Type::Uint(256),
+            Type::Address,
+            Type::FixedBytes(7),
+            Type::String,
+            Type::Bytes,
+            Type::Array(Box::new(Type::Bytes)),
+            Type::FixedArray(Box::new(Type::Array(Box::new(Type::Uint(8)))), 2),
+            Type::Tuple(vec![
+                ("a".to_string(), Type::String),
+                (
+                    "b".to_string(),
+                    Type::Array(Box::new(Type::Tuple(vec![("c".to_string(), Type::Bytes)]))),
+                ),
+            ]),
+        ];
+
+        for _ in 0..2000 {
+            let len = rng.gen_range(0..256);
+            let mut bs: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+
+            // make some words look like small offsets and lengths
+            for word in bs.chunks_mut(32) {
+                if word.len() == 32 && rng.gen_bool(0.5) {
+                    word[..31].iter_mut().for_each(|b| *b = 0);
+                    word[31] = rng.gen_range(0..8) * 32;
+                }
+            }
+
+            let n = rng.gen_range(1..4);
+            let random_tys: Vec<_> = (0..n)
+                .map(|_| tys[rng.gen_range(0..tys.len())].clone())
+                .collect();
+
+            // must never panic
+            let _ = Value::decode_from_slice(&bs, &random_tys);
+        }
+    }
+
     #[test]
     fn encode_uint() {
         let value = Value::Uint(U256::from(0xefcdab), 56);
@@ -715,6 +1712,159 @@ mod test {
         expected_bytes[29] = 0xab;
 
         assert_eq!(Value::encode(&[value]), expected_bytes);
+
+        // the lowest 8 bits are sign extended
+        let value = Value::Int(U256::from(0x1ff), 8);
+
+        assert_eq!(Value::encode(&[value]), vec![0xff; 32]);
+    }
+
+    #[test]
+    fn decode_negative_int() {
+        let mut bs = vec![0xffu8; 32];
+        bs[31] = 0x80;
+
+        let v = Value::decode_from_slice(&bs, &[Type::Int(8)]).expect("decode_from_slice failed");
+
+        assert_eq!(v, vec![Value::from_i64(-128, 8).unwrap()]);
+
+        // bits above the int size must be sign extended
+        let mut dirty = vec![vec![0u8; 32]; 3];
+        dirty[0][0] = 0x7f;
+        dirty[0][31] = 0x80;
+        dirty[1][30] = 1;
+        dirty[1][31] = 0x80;
+        dirty[2][31] = 0x80;
+
+        for bs in dirty {
+            assert!(matches!(
+                Value::decode_from_slice(&bs, &[Type::Int(8)]),
+                Err(Error::InvalidValue(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn fixed_round_trip() {
+        let values = vec![
+            Value::UFixed(U256::from(1_500_000_000_000_000_000u64), 128, 18),
+            Value::Fixed(twos_complement(true, U256::from(25)), 16, 2),
+            Value::Fixed(U256::from(7), 8, 0),
+        ];
+        let tys: Vec<_> = values.iter().map(Value::type_of).collect();
+
+        assert_eq!(
+            tys,
+            vec![Type::UFixed(128, 18), Type::Fixed(16, 2), Type::Fixed(8, 0)]
+        );
+
+        let bs = Value::try_encode(&values).expect("try_encode failed");
+
+        assert_eq!(
+            bs[32..64].to_vec(),
+            Value::encode(&[Value::from_i64(-25, 16).unwrap()])
+        );
+        assert_eq!(
+            Value::decode_from_slice(&bs, &tys).expect("decode_from_slice failed"),
+            values
+        );
+
+        assert_eq!(values[0].to_string(), "1.500000000000000000");
+        assert_eq!(values[1].to_string(), "-0.25");
+        assert_eq!(values[2].to_string(), "7");
+        assert!(values[1].is_negative());
+    }
+
+    #[test]
+    fn signed_int_conversions() {
+        let v = Value::from_i64(-5, 32).unwrap();
+
+        assert_eq!(v, Value::Int(U256::MAX - 4, 32));
+        assert!(v.is_negative());
+        assert_eq!(v.to_i128(), Some(-5));
+        assert_eq!(v.to_string(), "-5");
+
+        let v = Value::from_i64(i64::MAX, 64).unwrap();
+
+        assert!(!v.is_negative());
+        assert_eq!(v.to_i128(), Some(i64::MAX as i128));
+        assert_eq!(v.to_string(), i64::MAX.to_string());
+
+        // raw bits are interpreted according to the int size
+        assert_eq!(Value::Int(U256::from(0xff), 8).to_i128(), Some(-1));
+        assert_eq!(Value::Int(U256::from(0xff), 16).to_i128(), Some(0xff));
+
+        let min = Value::Int(U256::one() << 127, 128);
+
+        assert_eq!(min.to_i128(), Some(i128::MIN));
+        assert_eq!(min.to_string(), i128::MIN.to_string());
+
+        // the integer must fit in the int size
+        assert_eq!(Value::from_i64(127, 8).unwrap().to_i128(), Some(127));
+        assert_eq!(Value::from_i64(-128, 8).unwrap().to_i128(), Some(-128));
+        assert_eq!(
+            Value::from_i64(i64::MIN, 64).unwrap().to_i128(),
+            Some(i64::MIN as i128)
+        );
+
+        for (i, size) in [(128, 8), (-129, 8), (1000, 8), (1, 7), (1, 0), (1, 264)] {
+            assert!(
+                matches!(Value::from_i64(i, size), Err(Error::InvalidValue(_))),
+                "{} should not fit in int{}",
+                i,
+                size
+            );
+        }
+        assert_eq!(Value::Int(U256::one() << 127, 256).to_i128(), None);
+        assert_eq!(Value::Int(U256::MAX << 128, 256).to_i128(), None);
+        assert_eq!(Value::Uint(U256::MAX, 256).to_i128(), None);
+        assert_eq!(Value::Bool(true).to_i128(), None);
+    }
+
+    #[test]
+    fn display() {
+        let value = Value::Tuple(vec![
+            ("a".to_string(), Value::Uint(U256::from(7), 8)),
+            ("b".to_string(), Value::Bytes(vec![0xab, 0xcd])),
+            (
+                "c".to_string(),
+                Value::Array(
+                    vec![
+                        Value::String("x".to_string()),
+                        Value::String("y".to_string()),
+                    ],
+                    Type::String,
+                ),
+            ),
+            ("d".to_string(), Value::Bool(false)),
+        ]);
+
+        assert_eq!(value.to_string(), r#"(7, 0xabcd, ["x", "y"], false)"#);
+    }
+
+    #[test]
+    fn encode_decode_function() {
+        let address = H160::random();
+        let value = Value::Function {
+            address,
+            selector: [0xa9, 0x05, 0x9c, 0xbb],
+        };
+
+        let mut expected_bytes = address.as_bytes().to_vec();
+        expected_bytes.extend(&[0xa9, 0x05, 0x9c, 0xbb]);
+        expected_bytes.resize(32, 0);
+
+        let bs = Value::encode(std::slice::from_ref(&value));
+
+        assert_eq!(bs, expected_bytes);
+        assert_eq!(
+            Value::encode_packed(std::slice::from_ref(&value)).unwrap(),
+            expected_bytes[..24].to_vec()
+        );
+        assert_eq!(
+            Value::decode_from_slice(&bs, &[Type::Function]).expect("decode_from_slice failed"),
+            vec![value]
+        );
     }
 
     #[test]
@@ -844,6 +1994,149 @@ mod test {
         assert_eq!(Value::encode(&[value]), expected_bytes);
     }
 
+    #[test]
+    fn type_check() {
+        let uint8 = |i: u64| Value::Uint(U256::from(i), 8);
+
+        assert!(uint8(255).type_check(&Type::Uint(8)).is_ok());
+        assert!(Value::from_i64(-128, 8)
+            .unwrap()
+            .type_check(&Type::Int(8))
+            .is_ok());
+        assert!(Value::Tuple(vec![("a".to_string(), uint8(1))])
+            .type_check(&Type::Tuple(vec![("b".to_string(), Type::Uint(8))]))
+            .is_ok());
+
+        let invalid = vec![
+            (uint8(256), Type::Uint(8)),
+            (uint8(1), Type::Uint(16)),
+            (Value::Uint(U256::one(), 7), Type::Uint(7)),
+            (Value::Int(U256::from(128), 8), Type::Int(8)),
+            (Value::Int(U256::from(0xff), 8), Type::Int(8)),
+            (Value::FixedBytes(vec![0; 33]), Type::FixedBytes(33)),
+            (Value::FixedBytes(vec![0; 2]), Type::FixedBytes(3)),
+            (
+                Value::FixedArray(vec![uint8(1)], Type::Uint(8)),
+                Type::FixedArray(Box::new(Type::Uint(8)), 2),
+            ),
+            (
+                Value::Array(vec![uint8(1), Value::Bool(true)], Type::Uint(8)),
+                Type::Array(Box::new(Type::Uint(8))),
+            ),
+            (
+                Value::Array(vec![], Type::Uint(16)),
+                Type::Array(Box::new(Type::Uint(8))),
+            ),
+            (
+                Value::Tuple(vec![("a".to_string(), uint8(1))]),
+                Type::Tuple(vec![]),
+            ),
+            (Value::Tuple(vec![]), Type::Tuple(vec![])),
+            (
+                Value::FixedArray(vec![], Type::Uint(8)),
+                Type::FixedArray(Box::new(Type::Uint(8)), 0),
+            ),
+            (
+                Value::Array(vec![], Type::Tuple(vec![])),
+                Type::Array(Box::new(Type::Tuple(vec![]))),
+            ),
+            (Value::Bool(true), Type::Address),
+        ];
+
+        for (value, ty) in invalid {
+            assert!(
+                matches!(value.type_check(&ty), Err(Error::InvalidValue(_))),
+                "{:?} should not type check as {}",
+                value,
+                ty
+            );
+        }
+    }
+
+    #[test]
+    fn try_encode() {
+        let values = vec![
+            Value::Uint(U256::from(1), 8),
+            Value::Array(vec![Value::String("a".to_string())], Type::String),
+        ];
+
+        assert_eq!(
+            Value::try_encode(&values).expect("try_encode failed"),
+            Value::encode(&values)
+        );
+
+        assert!(Value::try_encode(&[Value::Uint(U256::from(256), 8)]).is_err());
+        assert!(Value::try_encode(&[Value::FixedBytes(vec![0; 33])]).is_err());
+        assert!(Value::try_encode(&[Value::Array(
+            vec![Value::Bool(true), Value::String("a".to_string())],
+            Type::Bool
+        )])
+        .is_err());
+    }
+
+    #[test]
+    fn encode_packed() {
+        let addr = H160::random();
+
+        let values = vec![
+            Value::Int(U256::MAX - 1, 16),
+            Value::Uint(U256::from(0x42), 8),
+            Value::String("Hello, world!".to_string()),
+            Value::Address(addr),
+            Value::Bool(true),
+            Value::FixedBytes(vec![0xab, 0xcd]),
+            Value::Array(
+                vec![Value::Uint(U256::from(1), 8), Value::Uint(U256::from(2), 8)],
+                Type::Uint(8),
+            ),
+        ];
+
+        let mut expected = hex::decode("fffe42").unwrap();
+        expected.extend(b"Hello, world!");
+        expected.extend(addr.as_bytes());
+        expected.extend(&[0x01, 0xab, 0xcd]);
+        expected.extend(&[0u8; 31]);
+        expected.push(1);
+        expected.extend(&[0u8; 31]);
+        expected.push(2);
+
+        assert_eq!(
+            Value::encode_packed(&values).expect("encode_packed failed"),
+            expected
+        );
+    }
+
+    #[test]
+    fn encode_packed_unsupported() {
+        let values = vec![
+            Value::Array(vec![Value::String("a".to_string())], Type::String),
+            Value::FixedArray(
+                vec![Value::Array(vec![], Type::Uint(8))],
+                Type::Array(Box::new(Type::Uint(8))),
+            ),
+            Value::Tuple(vec![("a".to_string(), Value::Bool(true))]),
+        ];
+
+        for value in values {
+            assert!(Value::encode_packed(&[value]).is_err());
+        }
+
+        let invalid = vec![
+            Value::Uint(U256::one(), 300),
+            Value::Int(U256::one(), 7),
+            Value::UFixed(U256::one(), 0, 18),
+            Value::Uint(U256::from(256), 8),
+            Value::FixedArray(vec![Value::Uint(U256::one(), 264)], Type::Uint(264)),
+        ];
+
+        for value in invalid {
+            assert!(matches!(
+                Value::encode_packed(&[value]),
+                Err(Error::InvalidValue(_))
+            ));
+        }
+    }
+
     #[test]
     fn encode_many() {
         let values = vec![